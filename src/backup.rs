@@ -0,0 +1,79 @@
+//! # backup :
+//! Ce module gère les sauvegardes du fichier sqlite3. Une sauvegarde est une copie horodatée
+//! faite avec l'API de sauvegarde en ligne de sqlite (la base peut être utilisée pendant la
+//! copie), placée dans le répertoire backup à côté du fichier. Seules les BACKUP_KEEP plus
+//! récentes sont conservées.
+//! - author : Thierry Probst <thierry.probst@free.fr>
+//! - version : 1.0.0
+//! - date : 19/10/2026
+use std::fs;
+use std::path::{Path, PathBuf};
+use chrono::Local;
+use rusqlite::{Connection, DatabaseName, Error, OpenFlags, Result};
+use rusqlite::backup::Progress;
+use crate::schema;
+
+
+/// le nom du répertoire des sauvegardes, placé à côté du fichier sqlite3
+pub const BACKUP_DIR: &str = "backup";
+/// le nombre de sauvegardes conservées, les plus anciennes sont supprimées
+pub const BACKUP_KEEP: usize = 10;
+
+/// backup : sauvegarde la base ouverte par la connexion puis supprime les sauvegardes les plus
+/// anciennes.
+/// - params :
+///     - conn -> une connexion sur le fichier sqlite3 à sauvegarder
+///     - reason -> la raison de la sauvegarde, ajoutée au nom du fichier (manual, migration, ...)
+/// - return : le chemin de la sauvegarde en cas de succès sinon l'erreur Sqlite3 (InvalidPath si
+///   le répertoire des sauvegardes ne peut pas être créé)
+pub fn backup(conn: &Connection, reason: &str) -> Result<PathBuf> {
+    let db = PathBuf::from( conn.path().unwrap_or_default() );
+    let stem = db.file_stem().unwrap_or_default().to_string_lossy().to_string();
+    let dir = db.parent().unwrap_or( Path::new(".") ).join( BACKUP_DIR );
+    fs::create_dir_all( &dir ).map_err( |_| Error::InvalidPath( dir.clone() ) )?;
+
+    let file = dir.join( format!( "{stem}-{}-{reason}.sq3", Local::now().format("%Y%m%d-%H%M%S") ) );
+    conn.backup( DatabaseName::Main, &file, None )?;
+    rotate( &dir, &stem );
+    Ok(file)
+}
+
+/// rotate : supprime les sauvegardes les plus anciennes pour n'en garder que BACKUP_KEEP. Le nom
+/// des fichiers commence par l'horodatage, l'ordre alphabétique est donc l'ordre chronologique.
+/// - params :
+///     - dir -> le répertoire des sauvegardes
+///     - stem -> le nom du fichier sqlite3 sauvegardé (sans extension)
+/// - return : aucun, une sauvegarde qui ne peut pas être supprimée est simplement conservée
+fn rotate(dir: &Path, stem: &str) {
+    let Ok(entries) = fs::read_dir( dir ) else { return; };
+    let mut files: Vec<PathBuf> = entries.filter_map( |e| e.ok() ).map( |e| e.path() )
+        .filter( |p| p.file_name().map( |n| n.to_string_lossy().starts_with( &format!("{stem}-") ) ).unwrap_or( false ) )
+        .filter( |p| p.extension().map( |e| e == "sq3" ).unwrap_or( false ) )
+        .collect();
+    files.sort();
+    let nb = files.len().saturating_sub( BACKUP_KEEP );
+    for f in files.iter().take( nb ) {
+        let _ = fs::remove_file( f );
+    }
+}
+
+/// restore : remplace le contenu du fichier sqlite3 par celui d'une sauvegarde. Le schéma de la
+/// sauvegarde est vérifié avant, et le fichier courant est lui-même sauvegardé.
+/// - params :
+///     - url -> le chemin du fichier sqlite3 à remplacer
+///     - file -> le chemin de la sauvegarde à restaurer
+/// - return : en cas de succès, la liste des problèmes trouvés dans la sauvegarde (vide si elle
+///   a été restaurée) et le chemin de la sauvegarde du fichier courant, sinon l'erreur Sqlite3
+pub fn restore(url: &str, file: &str) -> Result<(Vec<String>, Option<PathBuf>)> {
+    let src = Connection::open_with_flags( file, OpenFlags::SQLITE_OPEN_READ_ONLY )?;
+    let problems = schema::check( &src )?;
+    if !problems.is_empty() {
+        return Ok(( problems, None ));
+    }
+    drop( src );
+
+    let mut conn = Connection::open( url )?;
+    let saved = backup( &conn, "restore" )?;
+    conn.restore( DatabaseName::Main, file, None::<fn(Progress)> )?;
+    Ok(( Vec::new(), Some(saved) ))
+}
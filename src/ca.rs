@@ -1,18 +1,22 @@
 //! # ca :
 //! Ce module définit une structure Ca représentant une journée de travail.
-//! Cela correspondant à un enregistrement de la table CA du fichier Sqlite3
+//! Cela correspondant à un enregistrement de la vue CA du fichier Sqlite3, c'est à dire au
+//! total des entrées (activités) de la journée
 //! - author : Thierry Probst <thierry.probst@free.fr>
 //! - version : 1.0.0
 //! - date : 23/04/2023
 use std::fmt;
 use chrono::NaiveDate;
-use crate::CaDaoSqlite;
-use crate::Dialog;
-use crate::DB_URL;
+use crate::cadaosqlite::CaDaoSqlite;
+use crate::entry::Entry;
+use crate::error::{Error, Result};
+use crate::i18n;
 
 
 #[derive(Debug)]
 pub struct Ca {
+    /// l'identifiant du profil (la personne) concerné
+    pub profile: i64,
     /// la date du jour concerné
     pub date: String,
     /// le chiffre d'affaire réalisé
@@ -23,63 +27,74 @@ pub struct Ca {
     pub hsup: f32,
     /// un éventuel commentaire lié à la journée
     pub comment: Option<String>,
+    /// les tags associés à la journée (table CA_TAG)
+    pub tags: Vec<String>,
+    /// le détail des activités de la journée (table ENTRY)
+    pub entries: Vec<Entry>,
 }
 
 impl Ca {
     /// new : crée un objet chiffre d'affaire 'Ca' à partir date. L'objet est recherché dans la
     /// base sqlite3. Si il est trouvé, il est rapatrié en vue de son utilisation si non ce sont
     /// des valeurs génériques (day, 0.0 et None) qui sont utilisées pour initialiser les différents
-    /// membres
-    /// - params : day -> la date du chiffre d'affaire
-    /// - return : un objet Ca
-    pub fn new( day: &NaiveDate) -> Ca {
-        let sqlite = CaDaoSqlite{ url: DB_URL.to_string() };
-        let mut c = Ca{ date: day.to_string(), ca: 0.0, hours: 0.0, hsup: 0.0, comment: None };
-        match sqlite.exist( day ) {
-            Ok(exist) =>
-                if exist { // on récupère les données
-                    match sqlite.get( day ) {
-                        Ok(ca) => c = ca,
-                        Err(e) => Dialog::something_goes_wrong( "cadaoslite:get", e ),
-                    }
-                } else { // on sauvegarde le template
-                    match sqlite.add( &c ) {
-                        Ok(()) => println!( "{} a été créé dans la DB", c ),
-                        Err(e) => Dialog::something_goes_wrong( "cadaosqlite::add", e),
-                    }
-                },
-            Err(e) => Dialog::something_goes_wrong( "cadaosqlite:exist", e ),
+    /// membres. Ce jour vide n'est pas enregistré : il ne le sera que par Ca::save
+    /// - params :
+    ///     - sqlite -> le CaDaoSqlite à utiliser (cf. CaDaoSqlite::open_session), qui définit
+    ///       aussi le profil concerné
+    ///     - day -> la date du chiffre d'affaire
+    /// - return : un objet Ca (contenant une seule entrée vide s'il n'existe pas encore) sinon
+    ///   l'erreur Db
+    pub fn new( sqlite: &CaDaoSqlite, day: &NaiveDate ) -> Result<Ca> {
+        if sqlite.exist( day )? { // on récupère les données
+            return Ok( sqlite.get( day )? );
         }
-        return c;
+        Ok( Ca{ profile: sqlite.profile, date: day.to_string(), ca: 0.0, hours: 0.0, hsup: 0.0, comment: None,
+            tags: Vec::new(), entries: vec![ Entry::default() ] } )
     }
 
     /// delete : suppresion d'un objet ca dans la base de données sqlite3
-    /// - params : l'objet courant qui appelle la suppression
-    /// - return : aucun
-    pub fn delete(self) -> () {
-        let sqlite = CaDaoSqlite{ url: DB_URL.to_string() };
-        match sqlite.del( self ) {
-            Ok(()) => (),
-            Err(e) => Dialog::something_goes_wrong("cadaosqlite::del", e),
+    /// - params :
+    ///     - self -> l'objet courant qui appelle la suppression
+    ///     - sqlite -> le CaDaoSqlite à utiliser
+    /// - return : un objet () en cas de succés sinon l'erreur Db
+    pub fn delete(self, sqlite: &CaDaoSqlite) -> Result<()> {
+        Ok( sqlite.del( self )? )
+    }
+
+    /// save : enregistrement de l'objet Ca dans la base de données sqlite3, après avoir vérifié
+    /// ses entrées (cf. Entry::validate)
+    /// - params :
+    ///     - self -> l'objet courant qui appelle l'enregistrement
+    ///     - sqlite -> le CaDaoSqlite à utiliser
+    /// - return : un objet () en cas de succés sinon l'erreur Validation ou Db
+    pub fn save( &self, sqlite: &CaDaoSqlite ) -> Result<()> {
+        for e in self.entries.iter() {
+            e.validate().map_err( Error::Validation )?;
         }
+        Ok( sqlite.update( self )? )
     }
 
-    /// save : enregistrement de l'objet Ca dans la base de données sqlite3
-    /// - params : l'objet courant qui appelle l'enregistrement
+    /// total : recalcule les valeurs de la journée à partir de ses entrées
+    /// - params : l'objet courant
     /// - return : aucun
-    pub fn save( &self) -> () {
-        let sqlite = CaDaoSqlite{ url: DB_URL.to_string() };
-        match sqlite.update( &self ) {
-            Ok(()) => (),
-            Err(e) => Dialog::something_goes_wrong("cadaosqlite::update", e),
-        }
+    pub fn total(&mut self) {
+        self.ca = self.entries.iter().map( |e| e.ca ).sum();
+        self.hours = self.entries.iter().map( |e| e.hours ).sum();
+        self.hsup = self.entries.iter().map( |e| e.hsup ).sum();
+        let comments: Vec<&str> = self.entries.iter().filter_map( |e| e.comment.as_deref() ).collect();
+        self.comment = if comments.is_empty() { None } else { Some( comments.join(" / ") ) };
     }
 }
 
 impl fmt::Display for Ca {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         // Use `self.number` to refer to each positional data point.
-        write!(f, "({} : {} [{}/{} '{:?}'])", self.date, self.ca, self.hours, self.hsup, self.comment)
+        write!(f, "({} : {} [{}/{} '{:?}']", i18n::iso_date( &self.date ), i18n::decimal( self.ca ), i18n::decimal( self.hours ),
+            i18n::decimal( self.hsup ), self.comment)?;
+        for t in self.tags.iter() {
+            write!(f, " #{t}")?;
+        }
+        write!(f, ")")
     }
 
 }
@@ -1,21 +1,227 @@
 //! # cadaosqlite :
 //! Ce module définit un CaDaoSqlite contenant les données communes utilisées pour les requetes
 //! SQL d'accès à la table CA (cf. fichier ca.rs pour ce qui concerne la définition de cette table)
+//! Les données sont enregistrées dans la table ENTRY (cf. fichier entry.rs), la vue DAY donne le
+//! total de chaque journée. Toutes les requêtes sont limitées au profil du CaDaoSqlite (cf. fichier
+//! profile.rs), la vue CA est conservée pour l'application python.
 //! - author : Thierry Probst <thierry.probst@free.fr>
 //! - version : 1.0.0
 //! - date : 23/04/2023
 
+use std::rc::Rc;
 use chrono::prelude::*;
-use rusqlite::{Connection, Result, named_params };
+use rusqlite::{Connection, Result, Row, named_params };
+use rusqlite::types::Value;
+use rusqlite::vtab::array::{self, Array};
 use crate::ca::Ca;
+use crate::doctor::{Anomaly, Repair};
+use crate::entry::Entry;
+use crate::history::{History, SOURCE_CLI};
+use crate::pay::{Bracket, Payslip};
+use crate::period::{Period, PeriodTotal, Step};
+use crate::profile::Profile;
+use crate::schema;
+use crate::summary::Summary;
+use crate::tag::TagTotal;
+
+/// la condition SQL limitant une requête sur la table ENTRY ou la vue DAY aux jours portant l'un
+/// des tags passés dans :tags (aucune limitation si :nb_tags vaut 0)
+const TAG_FILTER: &str = "(:nb_tags = 0 OR date IN
+    (SELECT date FROM CA_TAG WHERE profile_id = :profile AND tag IN rarray(:tags)))";
 
 #[derive(Debug)]
 pub struct CaDaoSqlite {
     /// le chemin du fichier sqlite3 qui contient la table CA
     pub url: String,
+    /// l'identifiant du profil auquel sont limitées les requêtes
+    pub profile: i64,
+    /// l'origine enregistrée dans l'historique pour les modifications faites par ce CaDaoSqlite
+    pub source: &'static str,
+    /// la connexion partagée par une session interactive (cf. fonction open_session), None pour
+    /// ouvrir une connexion à chaque requête
+    pub session: Option<Rc<Connection>>,
 }
 
 impl CaDaoSqlite {
+    /// new : crée un CaDaoSqlite pour la ligne de commande
+    /// - params :
+    ///     - url -> le chemin du fichier sqlite3
+    ///     - profile -> l'identifiant du profil auquel sont limitées les requêtes
+    /// - return : un objet CaDaoSqlite
+    pub fn new(url: &str, profile: i64) -> CaDaoSqlite {
+        CaDaoSqlite{ url: url.to_string(), profile, source: SOURCE_CLI, session: None }
+    }
+
+    /// open_session : crée un CaDaoSqlite dont toutes les requêtes utilisent la même connexion,
+    /// ouverte jusqu'à ce qu'il soit libéré (cf. session interactive de la ligne de commande)
+    /// - params : &self -> une référence sur la structure contenant l'url
+    /// - return : le nouveau CaDaoSqlite en cas de succès sinon l'erreur Sqlite3
+    pub fn open_session(&self) -> Result<CaDaoSqlite> {
+        Ok( CaDaoSqlite{ url: self.url.clone(), profile: self.profile, source: self.source,
+            session: Some( Rc::new( self.open()? ) ) } )
+    }
+
+    /// connect : la connexion de la session si elle est ouverte, sinon une nouvelle connexion
+    /// - params : &self -> une référence sur la structure contenant l'url
+    /// - return : la connexion en cas de succès sinon l'erreur Sqlite3
+    fn connect(&self) -> Result<Rc<Connection>> {
+        match &self.session {
+            Some(conn) => Ok( conn.clone() ),
+            None => Ok( Rc::new( self.open()? ) ),
+        }
+    }
+
+    /// open : ouvre une connexion sur le fichier sqlite3 et met à jour son schéma si besoin.
+    /// - params : &self -> une référence sur la structure contenant l'url
+    /// - return : la connexion en cas de succès sinon l'erreur Sqlite3
+    fn open(&self) -> Result<Connection> {
+        let mut conn = Connection::open(&self.url)?;
+        schema::migrate( &mut conn )?;
+        array::load_module( &conn )?;
+        Ok(conn)
+    }
+
+    /// date_from_row : récupère une date au format yyyy-mm-dd dans une colonne d'une ligne
+    /// - params : row, idx -> la ligne et l'indice de la colonne
+    /// - return : la date, ou une erreur de conversion si la colonne n'est pas une date valide
+    fn date_from_row(row: &Row, idx: usize) -> Result<NaiveDate> {
+        let s: String = row.get( idx )?;
+        NaiveDate::parse_from_str( &s, "%Y-%m-%d" )
+            .map_err( |e| rusqlite::Error::FromSqlConversionFailure( idx, rusqlite::types::Type::Text, Box::new( e ) ) )
+    }
+
+    /// tag_array : prépare une liste de tags pour l'utiliser comme paramètre :tags de TAG_FILTER
+    /// - params : tags -> les tags
+    /// - return : un tableau utilisable avec la fonction sqlite rarray
+    fn tag_array(tags: &[String]) -> Array {
+        Rc::new( tags.iter().map( |t| Value::from( t.clone() ) ).collect() )
+    }
+
+    /// ca_from_row : construit un objet Ca à partir d'une ligne de la table CA.
+    /// - params : row -> la ligne retournée par une requête sur la table CA
+    /// - return : un objet Ca en cas de succès sinon l'erreur Sqlite3
+    fn ca_from_row(row: &Row) -> Result<Ca> {
+        Ok(Ca {
+            profile: row.get("profile_id")?,
+            date: row.get("date")?,
+            ca: row.get("ca")?,
+            hours: row.get("hours")?,
+            hsup: row.get("hsup")?,
+            comment: row.get("comment")?,
+            tags: Vec::new(),
+            entries: Vec::new(),
+        })
+    }
+
+    /// last_history : l'identifiant de la dernière modification enregistrée dans HISTORY
+    /// - params : conn -> la connexion utilisée
+    /// - return : l'identifiant (0 si l'historique est vide) sinon l'erreur Sqlite3
+    fn last_history(conn: &Connection) -> Result<i64> {
+        conn.query_row( "SELECT COALESCE(MAX(id), 0) FROM HISTORY", [], |r| r.get(0) )
+    }
+
+    /// sign_history : enregistre la source du CaDaoSqlite sur les modifications ajoutées à
+    /// l'historique (par les triggers) depuis last.
+    /// - params :
+    ///     - conn -> la connexion utilisée pour les modifications
+    ///     - last -> l'identifiant de la dernière modification avant celles à signer
+    /// - return : un objet () en cas de succés sinon l'erreur Sqlite3
+    fn sign_history(&self, conn: &Connection, last: i64) -> Result<()> {
+        conn.execute( "UPDATE HISTORY SET source = :source WHERE id > :last",
+            named_params!{ ":source": self.source, ":last": last } )?;
+        Ok(())
+    }
+
+    /// write_entries : enregistre les entrées du Ca dans la table ENTRY. Les entrées déjà
+    /// enregistrées sont mises à jour, les nouvelles sont ajoutées et celles qui ne font plus
+    /// partie du Ca sont placées dans la corbeille (cf. fonction del).
+    /// - params :
+    ///     - conn -> la connexion utilisée pour la sauvegarde du Ca
+    ///     - c -> une structure Ca contenant les entrées à enregistrer
+    /// - return : un objet () en cas de succés sinon l'erreur Sqlite3
+    fn write_entries(conn: &Connection, c: &Ca) -> Result<()> {
+        let mut stmt = conn.prepare( "SELECT id FROM ENTRY WHERE profile_id = :profile AND date = :date
+            AND deleted_at IS NULL" )?;
+        let rows = stmt.query_map( named_params!{ ":profile": c.profile, ":date": c.date }, |r| r.get(0) )?;
+        for id in rows {
+            let id: i64 = id?;
+            if !c.entries.iter().any( |e| e.id == Some(id) ) {
+                conn.execute( "UPDATE ENTRY SET deleted_at = strftime('%Y-%m-%d %H:%M:%S', 'now', 'localtime') WHERE id = :id",
+                    named_params!{ ":id": id } )?;
+            }
+        }
+        for e in c.entries.iter() {
+            let params = named_params!{ ":id": e.id, ":profile": c.profile, ":date": c.date, ":activity": e.activity,
+                ":ca": e.ca, ":hours": e.hours, ":hsup": e.hsup, ":comment": e.comment };
+            match e.id {
+                Some(_) => conn.execute( "UPDATE ENTRY SET activity = :activity, ca = :ca, hours = :hours,
+                    hsup = :hsup, comment = :comment WHERE id = :id AND profile_id = :profile AND date = :date", params )?,
+                None => conn.execute( "INSERT INTO ENTRY (id, profile_id, date, activity, ca, hours, hsup, comment)
+                    VALUES (:id, :profile, :date, :activity, :ca, :hours, :hsup, :comment)", params )?,
+            };
+        }
+        Ok(())
+    }
+
+    /// entries : récupère les entrées (activités) d'une date.
+    /// - params :
+    ///     - &self -> une référence sur la structure contenant l'url
+    ///     - d -> une référence sur la date concernée
+    /// - return : un Vecteur contenant les entrées en cas de succès sinon l'erreur Sqlite3
+    pub fn entries(&self, d: &NaiveDate) -> Result<Vec<Entry>> {
+        let conn = self.connect()?;
+
+        let mut stmt = conn.prepare( "SELECT * FROM ENTRY WHERE profile_id = :profile AND date = :day
+            AND deleted_at IS NULL ORDER BY id" )?;
+        let rows = stmt.query_map( named_params!{ ":profile": self.profile, ":day": d.format("%Y-%m-%d").to_string() },
+            |row| Ok(Entry {
+            id: row.get("id")?,
+            activity: row.get("activity")?,
+            ca: row.get("ca")?,
+            hours: row.get("hours")?,
+            hsup: row.get("hsup")?,
+            comment: row.get("comment")?,
+        }))?;
+        let mut entries = Vec::new();
+        for e in rows {
+            entries.push( e? );
+        }
+        Ok(entries)
+    }
+
+    /// write_tags : remplace les tags du Ca dans la table CA_TAG.
+    /// - params :
+    ///     - conn -> la connexion utilisée pour la sauvegarde du Ca
+    ///     - c -> une structure Ca contenant les tags à enregistrer
+    /// - return : un objet () en cas de succés sinon l'erreur Sqlite3
+    fn write_tags(conn: &Connection, c: &Ca) -> Result<()> {
+        conn.execute( "DELETE FROM CA_TAG WHERE profile_id = :profile AND date = :date",
+            named_params!{ ":profile": c.profile, ":date": c.date } )?;
+        let mut stmt = conn.prepare( "INSERT INTO CA_TAG (profile_id, date, tag) VALUES (:profile, :date, :tag)" )?;
+        for t in c.tags.iter() {
+            stmt.execute( named_params!{ ":profile": c.profile, ":date": c.date, ":tag": t } )?;
+        }
+        Ok(())
+    }
+
+    /// tags : récupère les tags associés à une date.
+    /// - params :
+    ///     - &self -> une référence sur la structure contenant l'url
+    ///     - d -> une référence sur la date concernée
+    /// - return : un Vecteur contenant les tags (triés) en cas de succès sinon l'erreur Sqlite3
+    pub fn tags(&self, d: &NaiveDate) -> Result<Vec<String>> {
+        let conn = self.connect()?;
+
+        let mut stmt = conn.prepare( "SELECT tag FROM CA_TAG WHERE profile_id = :profile AND date = :day ORDER BY tag" )?;
+        let rows = stmt.query_map( named_params!{ ":profile": self.profile, ":day": d.format("%Y-%m-%d").to_string() },
+            |r| r.get(0) )?;
+        let mut tags = Vec::new();
+        for t in rows {
+            tags.push( t? );
+        }
+        Ok(tags)
+    }
+
     /// exist : vérifie si un date est présente dans la table CA.
     /// - params :
     ///     - &self -> une référence sur la structure contenant l'url et la date
@@ -25,10 +231,11 @@ impl CaDaoSqlite {
     ///     - false -> sinon
     pub fn exist(&self, d: &NaiveDate ) -> Result<bool> {
         let mut result = false;
-        let conn = Connection::open(&self.url)?;
+        let conn = self.connect()?;
 
-        let sum: i32 = conn.query_row( "SELECT COUNT(*) FROM CA WHERE date = :day",
-            &[ (":day", d.format("%Y-%m-%d").to_string().as_str() )], |row| row.get(0) )?;
+        let sum: i32 = conn.query_row( "SELECT COUNT(*) FROM ENTRY WHERE profile_id = :profile AND date = :day
+            AND deleted_at IS NULL",
+            named_params!{ ":profile": self.profile, ":day": d.format("%Y-%m-%d").to_string() }, |row| row.get(0) )?;
         if sum > 0 { result = true; }
         Ok(result)
     }
@@ -39,32 +246,32 @@ impl CaDaoSqlite {
     ///    - d -> une référence sur une date du Ca à récuperer
     /// - return : un objet Ca en cas de succès sinon l'erreur Sqlite3
     pub fn get(&self, d: &NaiveDate ) -> Result<Ca> {
-        let conn = Connection::open(&self.url)?;
-
-        let ca: Ca = conn.query_row( "SELECT * FROM CA WHERE date = :day",
-            &[ (":day", d.format("%Y-%m-%d").to_string().as_str() )],
-            |row| Ok(Ca {
-                date: row.get("date")?,
-                ca: row.get("ca")?,
-                hours: row.get(2)?,
-                hsup: row.get(3)?,
-                comment: row.get("comment")?,
-            })
-        )?;
+        let conn = self.connect()?;
+
+        let mut ca: Ca = conn.query_row( "SELECT * FROM DAY WHERE profile_id = :profile AND date = :day",
+            named_params!{ ":profile": self.profile, ":day": d.format("%Y-%m-%d").to_string() },
+            CaDaoSqlite::ca_from_row )?;
+        ca.tags = self.tags( d )?;
+        ca.entries = self.entries( d )?;
         Ok(ca)
     }
 
-    /// del : supprime le Ca dans la DB.
+    /// del : supprime le Ca dans la DB. La suppression est logique : les entrées sont placées dans
+    /// la corbeille (deleted_at) et peuvent être restaurées jusqu'à leur purge (cf. fonction purge).
     /// - params :
     ///     - &self -> une référence sur la structure contenant l'url et la date
     ///     - c -> une structure Ca contenant les données a supprimer
     /// - return : un objet () en cas de succés sinon l'erreur Sqlite3
     pub fn del(&self, c: Ca) -> Result<()> {
-        let conn = Connection::open(&self.url)?;
+        let conn = self.connect()?;
 
-        let mut stmt = conn.prepare( "DELETE FROM CA WHERE date = :date" )?;
-        stmt.execute( named_params!{ ":date": c.date } )?;
-        Ok(())
+        let tx = conn.unchecked_transaction()?;
+        let last = CaDaoSqlite::last_history( &tx )?;
+        tx.execute( "UPDATE ENTRY SET deleted_at = strftime('%Y-%m-%d %H:%M:%S', 'now', 'localtime')
+            WHERE profile_id = :profile AND date = :date AND deleted_at IS NULL",
+            named_params!{ ":profile": c.profile, ":date": c.date } )?;
+        self.sign_history( &tx, last )?;
+        tx.commit()
     }
 
     /// update : sauvegarde le Ca dans la DB.
@@ -73,14 +280,14 @@ impl CaDaoSqlite {
     ///     - c -> une structure Ca contenant les données a supprimer
     /// - return : un objet () en cas de succés sinon l'erreur Sqlite3
     pub fn update(&self, c: &Ca) -> Result<()> {
-        let conn = Connection::open(&self.url)?;
+        let conn = self.connect()?;
 
-        let mut stmt = conn.prepare( "UPDATE CA
-                            SET ca = :ca, hours = :hours, hsup = :hsup, comment = :comment
-                            WHERE date = :date" )?;
-        stmt.execute( named_params!{ ":ca": c.ca, ":hours": c.hours,
-                ":hsup": c.hsup, ":comment": c.comment, ":date": c.date } )?;
-        Ok(())
+        let tx = conn.unchecked_transaction()?;
+        let last = CaDaoSqlite::last_history( &tx )?;
+        CaDaoSqlite::write_entries( &tx, c )?;
+        CaDaoSqlite::write_tags( &tx, c )?;
+        self.sign_history( &tx, last )?;
+        tx.commit()
     }
 
     /// add : enregistre le Ca dans la DB.
@@ -89,14 +296,14 @@ impl CaDaoSqlite {
     ///     - c -> une structure Ca contenant les données a enregistrer
     /// - return : un objet () en cas de succés sinon l'erreur Sqlite3
     pub fn add(&self, c: &Ca) -> Result<()> {
-        let conn = Connection::open(&self.url)?;
+        let conn = self.connect()?;
 
-        let mut stmt = conn.prepare( "INSERT INTO CA (date, ca, hours, hsup, comment) VALUES
-        (:date, :ca, :hours, :hsup, :comment)" )?;
-        stmt.execute(
-            named_params!{ ":date": c.date, ":ca": c.ca, ":hours": c.hours, ":hsup": c.hsup, ":comment": c.comment }
-        )?;
-        Ok(())
+        let tx = conn.unchecked_transaction()?;
+        let last = CaDaoSqlite::last_history( &tx )?;
+        CaDaoSqlite::write_entries( &tx, c )?;
+        CaDaoSqlite::write_tags( &tx, c )?;
+        self.sign_history( &tx, last )?;
+        tx.commit()
     }
 
 
@@ -104,20 +311,15 @@ impl CaDaoSqlite {
     /// - params :
     ///     - &self -> une référence sur la structure contenant l'url et la date
     ///     - d -> une référence sur une date qui servira dans la requete d'interrogation de la DB
+    ///     - tags -> limite les données aux jours portant l'un de ces tags (si non vide)
     /// - return : un Vecteur contenant des objet Ca en cas de succès sinon l'erreur Sqlite3
-    pub fn datas_for_month(&self, d: &NaiveDate) -> Result<Vec<Ca>> {
-        let conn = Connection::open(&self.url)?;
-
-        let mut stmt = conn.prepare( "SELECT * FROM CA WHERE date LIKE :month ORDER BY date" )?;
-        let rows = stmt.query_map(&[(":month", d.format("%Y-%m%%").to_string().as_str() )], |row| {
-            Ok(Ca {
-                date: row.get("date")?,
-                ca: row.get("ca")?,
-                hours: row.get(2)?,
-                hsup: row.get(3)?,
-                comment: row.get("comment")?,
-            })
-        })?;
+    pub fn datas_for_month(&self, d: &NaiveDate, tags: &[String]) -> Result<Vec<Ca>> {
+        let conn = self.connect()?;
+
+        let mut stmt = conn.prepare( &format!( "SELECT * FROM DAY WHERE profile_id = :profile AND date LIKE :month
+            AND {TAG_FILTER} ORDER BY date" ) )?;
+        let rows = stmt.query_map( named_params!{ ":profile": self.profile, ":month": d.format("%Y-%m%%").to_string(),
+            ":nb_tags": tags.len(), ":tags": CaDaoSqlite::tag_array( tags ) }, CaDaoSqlite::ca_from_row )?;
         let mut ca_tab = Vec::new();
         for ca in rows {
             ca_tab.push( ca? );
@@ -125,17 +327,46 @@ impl CaDaoSqlite {
         Ok(ca_tab)
     }
 
+    /// days : récupère les jours d'une période, avec leurs tags.
+    /// - params :
+    ///     - &self -> une référence sur la structure contenant l'url
+    ///     - start, end -> le premier jour de la période et le premier jour qui la suit
+    /// - return : un Vecteur contenant des objet Ca (triés par date) en cas de succès sinon l'erreur
+    ///   Sqlite3
+    pub fn days(&self, start: &NaiveDate, end: &NaiveDate) -> Result<Vec<Ca>> {
+        let conn = self.connect()?;
+
+        let mut stmt = conn.prepare( "SELECT DAY.*, (SELECT group_concat(tag, ',') FROM
+                (SELECT tag FROM CA_TAG WHERE CA_TAG.profile_id = DAY.profile_id AND CA_TAG.date = DAY.date ORDER BY tag)) AS tags
+            FROM DAY WHERE profile_id = :profile AND date >= :start AND date < :end ORDER BY date" )?;
+        let rows = stmt.query_map( named_params!{ ":profile": self.profile, ":start": start.format("%Y-%m-%d").to_string(),
+            ":end": end.format("%Y-%m-%d").to_string() }, |row| {
+            let mut c = CaDaoSqlite::ca_from_row( row )?;
+            let tags: Option<String> = row.get("tags")?;
+            c.tags = tags.map( |t| t.split(',').map( |s| s.to_string() ).collect() ).unwrap_or_default();
+            Ok(c)
+        })?;
+        let mut days = Vec::new();
+        for c in rows {
+            days.push( c? );
+        }
+        Ok(days)
+    }
+
     /// ca_for_mount : récupère le chiffre d'affaire du mois choisi.
     /// - params :
     ///     - &self -> une référence sur la structure contenant l'url et la date
     ///     - d -> une référence sur une date qui servira dans la requete d'interrogation de la DB
-    /// - return : un float contenant la valeur du chiffre d'affaire en cas de succès
-    /// sinon l'erreur Sqlite3
-    pub fn ca_for_month(&self, d : &NaiveDate ) -> Result<f64> {
-        let conn = Connection::open(&self.url)?;
+    ///     - tags -> limite le calcul aux jours portant l'un de ces tags (si non vide)
+    /// - return : un float contenant la valeur du chiffre d'affaire (0 pour un mois sans
+    ///   données) en cas de succès sinon l'erreur Sqlite3
+    pub fn ca_for_month(&self, d : &NaiveDate , tags: &[String]) -> Result<f64> {
+        let conn = self.connect()?;
 
-        let ca: f64 = conn.query_row( "SELECT SUM(ca) FROM CA WHERE date LIKE :month",
-            &[ (":month", d.format("%Y-%m%%").to_string().as_str() )],
+        let ca: f64 = conn.query_row( &format!( "SELECT TOTAL(ca) FROM ENTRY WHERE profile_id = :profile AND date LIKE :month
+            AND deleted_at IS NULL AND {TAG_FILTER}" ),
+            named_params!{ ":profile": self.profile, ":month": d.format("%Y-%m%%").to_string(), ":nb_tags": tags.len(),
+                ":tags": CaDaoSqlite::tag_array( tags ) },
             |r| r.get(0) )?;
         Ok(ca)
     }
@@ -144,13 +375,16 @@ impl CaDaoSqlite {
     /// - params :
     ///     - &self -> une référence sur la structure contenant l'url et la date
     ///     - d -> une référence sur une date qui servira dans la requete d'interrogation de la DB
-    /// - return : un float contenant la valeur du nombre d'heures en cas de succès
-    /// sinon l'erreur Sqlite3
-    pub fn hours_for_month(&self, d: &NaiveDate) -> Result<f64> {
-        let conn = Connection::open(&self.url)?;
+    ///     - tags -> limite le calcul aux jours portant l'un de ces tags (si non vide)
+    /// - return : un float contenant la valeur du nombre d'heures (0 pour un mois sans
+    ///   données) en cas de succès sinon l'erreur Sqlite3
+    pub fn hours_for_month(&self, d: &NaiveDate, tags: &[String]) -> Result<f64> {
+        let conn = self.connect()?;
 
-        let hours: f64 = conn.query_row( "SELECT SUM(hours) FROM CA WHERE date LIKE :month",
-            &[ (":month", d.format("%Y-%m%%").to_string().as_str() )],
+        let hours: f64 = conn.query_row( &format!( "SELECT TOTAL(hours) FROM ENTRY WHERE profile_id = :profile AND date LIKE :month
+            AND deleted_at IS NULL AND {TAG_FILTER}" ),
+            named_params!{ ":profile": self.profile, ":month": d.format("%Y-%m%%").to_string(), ":nb_tags": tags.len(),
+                ":tags": CaDaoSqlite::tag_array( tags ) },
             |r| r.get(0) )?;
         Ok(hours)
     }
@@ -159,14 +393,610 @@ impl CaDaoSqlite {
     /// - params :
     ///     - &self -> une référence sur la structure contenant l'url et la date
     ///     - d -> une référence sur une date qui servira dans la requete d'interrogation de la DB
-    /// - return : un float contenant la valeur du nombre d'heures supplémentaires en cas de succès
-    /// sinon l'erreur Sqlite3
-    pub fn hsup_for_month(&self, d: &NaiveDate ) -> Result<f64> {
-        let conn = Connection::open(&self.url)?;
+    ///     - tags -> limite le calcul aux jours portant l'un de ces tags (si non vide)
+    /// - return : un float contenant la valeur du nombre d'heures supplémentaires (0 pour un mois sans
+    ///   données) en cas de succès sinon l'erreur Sqlite3
+    pub fn hsup_for_month(&self, d: &NaiveDate , tags: &[String]) -> Result<f64> {
+        let conn = self.connect()?;
 
-        let hsup: f64 = conn.query_row( "SELECT SUM(hsup) FROM CA WHERE date LIKE :month",
-            &[ (":month", d.format("%Y-%m%%").to_string().as_str() )],
+        let hsup: f64 = conn.query_row( &format!( "SELECT TOTAL(hsup) FROM ENTRY WHERE profile_id = :profile AND date LIKE :month
+            AND deleted_at IS NULL AND {TAG_FILTER}" ),
+            named_params!{ ":profile": self.profile, ":month": d.format("%Y-%m%%").to_string(), ":nb_tags": tags.len(),
+                ":tags": CaDaoSqlite::tag_array( tags ) },
             |r| r.get(0) )?;
         Ok(hsup)
     }
+
+    /// tag_totals : calcule le chiffre d'affaire, les heures et le nombre de jours pour chacun des
+    /// tags utilisés sur une période.
+    /// - params :
+    ///     - &self -> une référence sur la structure contenant l'url
+    ///     - start, end -> le premier jour de la période et le premier jour qui la suit
+    ///     - tags -> limite le calcul à ces tags (si non vide)
+    /// - return : un Vecteur contenant un TagTotal par tag en cas de succès sinon l'erreur Sqlite3
+    pub fn tag_totals(&self, start: &NaiveDate, end: &NaiveDate, tags: &[String]) -> Result<Vec<TagTotal>> {
+        let conn = self.connect()?;
+
+        let mut stmt = conn.prepare( "SELECT CA_TAG.tag, TOTAL(DAY.ca), TOTAL(DAY.hours), COUNT(*)
+            FROM CA_TAG JOIN DAY ON DAY.profile_id = CA_TAG.profile_id AND DAY.date = CA_TAG.date
+            WHERE CA_TAG.profile_id = :profile AND DAY.date >= :start AND DAY.date < :end
+            AND (:nb_tags = 0 OR CA_TAG.tag IN rarray(:tags))
+            GROUP BY CA_TAG.tag ORDER BY CA_TAG.tag" )?;
+        let rows = stmt.query_map( named_params!{ ":profile": self.profile, ":start": start.format("%Y-%m-%d").to_string(),
+            ":end": end.format("%Y-%m-%d").to_string(), ":nb_tags": tags.len(), ":tags": CaDaoSqlite::tag_array( tags ) },
+            |r| Ok( TagTotal{ tag: r.get(0)?, ca: r.get(1)?, hours: r.get(2)?, days: r.get(3)? } ) )?;
+        let mut totals = Vec::new();
+        for t in rows {
+            totals.push( t? );
+        }
+        Ok(totals)
+    }
+
+    /// totals : calcule le chiffre d'affaire, les heures, les heures supplémentaires et le nombre
+    /// de jours d'une période, par jour, par semaine ou par mois.
+    /// - params :
+    ///     - &self -> une référence sur la structure contenant l'url
+    ///     - start, end -> le premier jour de la période et le premier jour qui la suit
+    ///     - step -> le regroupement des jours
+    /// - return : un Vecteur contenant un PeriodTotal par regroupement ayant des données (triés par
+    ///   date) en cas de succès sinon l'erreur Sqlite3
+    pub fn totals(&self, start: &NaiveDate, end: &NaiveDate, step: Step) -> Result<Vec<PeriodTotal>> {
+        let conn = self.connect()?;
+        let group = match step {
+            Step::Day => "date",
+            Step::Week => "date(date, '-6 days', 'weekday 1')",
+            Step::Month => "substr(date, 1, 8) || '01'",
+        };
+
+        let mut stmt = conn.prepare( &format!( "SELECT {group} AS start, TOTAL(ca), TOTAL(hours), TOTAL(hsup), COUNT(*)
+            FROM DAY WHERE profile_id = :profile AND date >= :start AND date < :end
+            GROUP BY 1 ORDER BY 1" ) )?;
+        let rows = stmt.query_map( named_params!{ ":profile": self.profile, ":start": start.format("%Y-%m-%d").to_string(),
+            ":end": end.format("%Y-%m-%d").to_string() },
+            |r| Ok( PeriodTotal{ start: CaDaoSqlite::date_from_row( r, 0 )?, ca: r.get(1)?, hours: r.get(2)?, hsup: r.get(3)?, days: r.get(4)? } ) )?;
+        let mut totals = Vec::new();
+        for t in rows {
+            totals.push( t? );
+        }
+        Ok(totals)
+    }
+
+    /// summary : calcule les totaux d'une période et le nombre de mois dont le chiffre d'affaire
+    /// dépasse un seuil, en une seule requête.
+    /// - params :
+    ///     - &self -> une référence sur la structure contenant l'url
+    ///     - period -> la période concernée
+    ///     - threshold -> le seuil mensuel de la prime
+    /// - return : un objet Summary en cas de succès sinon l'erreur Sqlite3
+    pub fn summary(&self, period: &Period, threshold: f64) -> Result<Summary> {
+        let conn = self.connect()?;
+
+        conn.query_row( "SELECT TOTAL(ca), TOTAL(hours), TOTAL(hsup), COUNT(*), TOTAL(ca > :threshold)
+            FROM (SELECT TOTAL(ca) AS ca, TOTAL(hours) AS hours, TOTAL(hsup) AS hsup FROM DAY
+                WHERE profile_id = :profile AND date >= :start AND date < :end GROUP BY substr(date, 1, 7))",
+            named_params!{ ":profile": self.profile, ":start": period.start.format("%Y-%m-%d").to_string(),
+                ":end": period.end().format("%Y-%m-%d").to_string(), ":threshold": threshold },
+            |r| Ok( Summary{ period: period.clone(), ca: r.get(0)?, hours: r.get(1)?, hsup: r.get(2)?, months: r.get(3)?,
+                above: r.get::<_, f64>(4)? as usize } ) )
+    }
+
+    /// search : recherche plein texte dans les commentaires des activités. Un jour dont plusieurs
+    /// activités correspondent n'est listé qu'une fois, avec les extraits de chacune.
+    /// - params :
+    ///     - &self -> une référence sur la structure contenant l'url
+    ///     - terms -> les termes recherchés (tous doivent être présents, un terme terminé par
+    ///       '*' est un préfixe)
+    ///     - start, end -> les marqueurs placés autour des termes trouvés dans l'extrait
+    /// - return : un Vecteur contenant les Ca trouvés (triés par date) accompagnés des extraits
+    ///   des commentaires (séparés par " / ")
+    pub fn search(&self, terms: &[String], start: &str, end: &str) -> Result<Vec<(Ca, String)>> {
+        let conn = self.connect()?;
+        let query: Vec<String> = terms.iter().map( |t| match t.strip_suffix('*') {
+            Some(p) => format!( "\"{}\"*", p.replace('"', "\"\"") ),
+            None => format!( "\"{}\"", t.replace('"', "\"\"") ),
+        }).collect();
+
+        // les extraits sont calculés par activité, puis regroupés par jour
+        let mut stmt = conn.prepare( "SELECT DAY.*, group_concat(found.snippet, ' / ') AS snippet
+            FROM (SELECT ENTRY.profile_id, ENTRY.date, snippet(CA_FTS, 1, :start, :end, '…', 10) AS snippet
+                FROM CA_FTS JOIN ENTRY ON ENTRY.id = CA_FTS.rowid
+                WHERE CA_FTS MATCH :query AND ENTRY.profile_id = :profile ORDER BY ENTRY.id) AS found
+            JOIN DAY ON DAY.profile_id = found.profile_id AND DAY.date = found.date
+            GROUP BY DAY.date ORDER BY DAY.date" )?;
+        let rows = stmt.query_map( named_params!{ ":profile": self.profile, ":start": start, ":end": end, ":query": query.join(" ") },
+            |row| Ok(( CaDaoSqlite::ca_from_row( row )?, row.get("snippet")? )) )?;
+        let mut result = Vec::new();
+        for r in rows {
+            result.push( r? );
+        }
+        Ok(result)
+    }
+
+    /// profile : récupère un profil par son nom, ou le profil par défaut.
+    /// - params :
+    ///     - &self -> une référence sur la structure contenant l'url
+    ///     - name -> le nom du profil, None pour le profil par défaut
+    /// - return : un objet Profile en cas de succès sinon l'erreur Sqlite3 (QueryReturnedNoRows si
+    ///   le profil n'existe pas)
+    pub fn profile(&self, name: Option<&str>) -> Result<Profile> {
+        let conn = self.connect()?;
+
+        conn.query_row( "SELECT * FROM PROFILE WHERE name = :name OR (:name IS NULL AND id = :default)",
+            named_params!{ ":name": name, ":default": crate::profile::DEFAULT_PROFILE },
+            CaDaoSqlite::profile_from_row )
+    }
+
+    /// profiles : récupère la liste des profils.
+    /// - params : &self -> une référence sur la structure contenant l'url
+    /// - return : un Vecteur contenant les profils en cas de succès sinon l'erreur Sqlite3
+    pub fn profiles(&self) -> Result<Vec<Profile>> {
+        let conn = self.connect()?;
+
+        let mut stmt = conn.prepare( "SELECT * FROM PROFILE ORDER BY id" )?;
+        let rows = stmt.query_map( [], CaDaoSqlite::profile_from_row )?;
+        let mut profiles = Vec::new();
+        for p in rows {
+            profiles.push( p? );
+        }
+        Ok(profiles)
+    }
+
+    /// save_profile : crée ou met à jour un profil (le nom sert de clé).
+    /// - params :
+    ///     - &self -> une référence sur la structure contenant l'url
+    ///     - p -> le profil à enregistrer (son id est ignoré)
+    /// - return : un objet () en cas de succés sinon l'erreur Sqlite3
+    pub fn save_profile(&self, p: &Profile) -> Result<()> {
+        let conn = self.connect()?;
+
+        conn.execute( "INSERT INTO PROFILE (name, threshold, rate, wage, overtime) VALUES (:name, :threshold, :rate, :wage, :overtime)
+            ON CONFLICT(name) DO UPDATE SET threshold = excluded.threshold, rate = excluded.rate, wage = excluded.wage,
+                overtime = excluded.overtime",
+            named_params!{ ":name": p.name, ":threshold": p.threshold, ":rate": p.rate, ":wage": p.wage,
+                ":overtime": Bracket::to_list( &p.overtime ) } )?;
+        Ok(())
+    }
+
+    /// profile_from_row : construit un objet Profile à partir d'une ligne de la table PROFILE.
+    /// - params : row -> la ligne retournée par une requête sur la table PROFILE
+    /// - return : un objet Profile en cas de succès sinon l'erreur Sqlite3
+    fn profile_from_row(row: &Row) -> Result<Profile> {
+        let overtime: String = row.get("overtime")?;
+        Ok(Profile {
+            id: row.get("id")?,
+            name: row.get("name")?,
+            threshold: row.get("threshold")?,
+            rate: row.get("rate")?,
+            wage: row.get("wage")?,
+            overtime: Bracket::parse_list( &overtime ).ok_or( rusqlite::Error::InvalidColumnType(
+                row.as_ref().column_index( "overtime" )?, "overtime".to_string(), rusqlite::types::Type::Text ) )?,
+        })
+    }
+
+    /// payslips : récupère les bulletins de paie d'une période.
+    /// - params :
+    ///     - &self -> une référence sur la structure contenant l'url
+    ///     - start, end -> le premier jour de la période et le premier jour qui la suit
+    /// - return : un Vecteur contenant les bulletins (triés par mois) en cas de succès sinon
+    ///   l'erreur Sqlite3
+    pub fn payslips(&self, start: &NaiveDate, end: &NaiveDate) -> Result<Vec<Payslip>> {
+        let conn = self.connect()?;
+
+        let mut stmt = conn.prepare( "SELECT month || '-01', gross, net, prime, overtime FROM PAYSLIP
+            WHERE profile_id = :profile AND month >= :start AND month < :end ORDER BY month" )?;
+        let rows = stmt.query_map( named_params!{ ":profile": self.profile, ":start": start.format("%Y-%m").to_string(),
+            ":end": end.format("%Y-%m").to_string() },
+            |r| Ok( Payslip{ month: CaDaoSqlite::date_from_row( r, 0 )?, gross: r.get(1)?, net: r.get(2)?, prime: r.get(3)?,
+                overtime: r.get(4)? } ) )?;
+        let mut payslips = Vec::new();
+        for p in rows {
+            payslips.push( p? );
+        }
+        Ok(payslips)
+    }
+
+    /// save_payslip : crée ou remplace le bulletin de paie d'un mois.
+    /// - params :
+    ///     - &self -> une référence sur la structure contenant l'url
+    ///     - p -> le bulletin à enregistrer
+    /// - return : un objet () en cas de succés sinon l'erreur Sqlite3
+    pub fn save_payslip(&self, p: &Payslip) -> Result<()> {
+        let conn = self.connect()?;
+
+        conn.execute( "INSERT OR REPLACE INTO PAYSLIP (profile_id, month, gross, net, prime, overtime)
+            VALUES (:profile, :month, :gross, :net, :prime, :overtime)",
+            named_params!{ ":profile": self.profile, ":month": p.month.format("%Y-%m").to_string(), ":gross": p.gross,
+                ":net": p.net, ":prime": p.prime, ":overtime": p.overtime } )?;
+        Ok(())
+    }
+
+    /// history : récupère l'historique des modifications d'une date.
+    /// - params :
+    ///     - &self -> une référence sur la structure contenant l'url
+    ///     - d -> une référence sur la date concernée
+    /// - return : un Vecteur contenant les modifications (de la plus ancienne à la plus récente)
+    ///   en cas de succès sinon l'erreur Sqlite3
+    pub fn history(&self, d: &NaiveDate) -> Result<Vec<History>> {
+        let conn = self.connect()?;
+
+        let mut stmt = conn.prepare( "SELECT * FROM HISTORY WHERE profile_id = :profile AND date = :day ORDER BY id" )?;
+        let rows = stmt.query_map( named_params!{ ":profile": self.profile, ":day": d.format("%Y-%m-%d").to_string() },
+            CaDaoSqlite::history_from_row )?;
+        let mut history = Vec::new();
+        for h in rows {
+            history.push( h? );
+        }
+        Ok(history)
+    }
+
+    /// restore : annule une modification de l'historique, l'entrée concernée retrouve les valeurs
+    /// qu'elle avait avant cette modification (une entrée ajoutée est donc supprimée, une entrée
+    /// mise à la corbeille en est retirée et inversement).
+    /// - params :
+    ///     - &self -> une référence sur la structure contenant l'url
+    ///     - id -> l'identifiant de la modification dans la table HISTORY
+    /// - return : la modification annulée en cas de succès sinon l'erreur Sqlite3
+    ///   (QueryReturnedNoRows si elle n'existe pas pour ce profil)
+    pub fn restore(&self, id: i64) -> Result<History> {
+        let conn = self.connect()?;
+
+        let tx = conn.unchecked_transaction()?;
+        let h = tx.query_row( "SELECT * FROM HISTORY WHERE id = :id AND profile_id = :profile",
+            named_params!{ ":id": id, ":profile": self.profile }, CaDaoSqlite::history_from_row )?;
+        let last = CaDaoSqlite::last_history( &tx )?;
+        match (h.op.as_str(), &h.old) {
+            (_, None) => tx.execute( "DELETE FROM ENTRY WHERE id = :id", named_params!{ ":id": h.entry_id } )?,
+            ("untrash", Some(_)) => tx.execute( "UPDATE ENTRY SET deleted_at = strftime('%Y-%m-%d %H:%M:%S', 'now', 'localtime')
+                WHERE id = :id", named_params!{ ":id": h.entry_id } )?,
+            (_, Some(e)) => tx.execute( "INSERT INTO ENTRY (id, profile_id, date, activity, ca, hours, hsup, comment)
+                VALUES (:id, :profile, :date, :activity, :ca, :hours, :hsup, :comment)
+                ON CONFLICT(id) DO UPDATE SET date = excluded.date, activity = excluded.activity,
+                ca = excluded.ca, hours = excluded.hours, hsup = excluded.hsup, comment = excluded.comment,
+                deleted_at = NULL",
+                named_params!{ ":id": h.entry_id, ":profile": self.profile, ":date": h.date, ":activity": e.activity,
+                    ":ca": e.ca, ":hours": e.hours, ":hsup": e.hsup, ":comment": e.comment } )?,
+        };
+        self.sign_history( &tx, last )?;
+        tx.commit()?;
+        Ok(h)
+    }
+
+    /// trash : récupère les journées placées dans la corbeille, c'est à dire le total de leurs
+    /// entrées supprimées.
+    /// - params : &self -> une référence sur la structure contenant l'url
+    /// - return : un Vecteur contenant les Ca supprimés (triés par date) accompagnés de la date de
+    ///   leur suppression en cas de succès sinon l'erreur Sqlite3
+    pub fn trash(&self) -> Result<Vec<(Ca, String)>> {
+        let conn = self.connect()?;
+
+        let mut stmt = conn.prepare( "SELECT profile_id, date, SUM(ca) AS ca, SUM(hours) AS hours, SUM(hsup) AS hsup,
+            group_concat(comment, ' / ') AS comment, MAX(deleted_at) AS deleted_at
+            FROM ENTRY WHERE profile_id = :profile AND deleted_at IS NOT NULL GROUP BY date ORDER BY date" )?;
+        let rows = stmt.query_map( named_params!{ ":profile": self.profile },
+            |row| Ok(( CaDaoSqlite::ca_from_row( row )?, row.get("deleted_at")? )) )?;
+        let mut result = Vec::new();
+        for r in rows {
+            result.push( r? );
+        }
+        Ok(result)
+    }
+
+    /// untrash : retire de la corbeille les entrées supprimées d'une date.
+    /// - params :
+    ///     - &self -> une référence sur la structure contenant l'url
+    ///     - d -> une référence sur la date concernée
+    /// - return : le nombre d'entrées restaurées en cas de succès sinon l'erreur Sqlite3
+    pub fn untrash(&self, d: &NaiveDate) -> Result<usize> {
+        let conn = self.connect()?;
+
+        let tx = conn.unchecked_transaction()?;
+        let last = CaDaoSqlite::last_history( &tx )?;
+        let nb = tx.execute( "UPDATE ENTRY SET deleted_at = NULL
+            WHERE profile_id = :profile AND date = :day AND deleted_at IS NOT NULL",
+            named_params!{ ":profile": self.profile, ":day": d.format("%Y-%m-%d").to_string() } )?;
+        self.sign_history( &tx, last )?;
+        tx.commit()?;
+        Ok(nb)
+    }
+
+    /// trash_days : récupère la durée de conservation des entrées de la corbeille.
+    /// - params : &self -> une référence sur la structure contenant l'url
+    /// - return : le nombre de jours en cas de succès sinon l'erreur Sqlite3
+    pub fn trash_days(&self) -> Result<i64> {
+        let conn = self.connect()?;
+
+        conn.query_row( "SELECT CAST(value AS INTEGER) FROM SETTING WHERE key = 'trash_days'", [], |r| r.get(0) )
+    }
+
+    /// set_trash_days : modifie la durée de conservation des entrées de la corbeille.
+    /// - params :
+    ///     - &self -> une référence sur la structure contenant l'url
+    ///     - days -> le nombre de jours
+    /// - return : un objet () en cas de succés sinon l'erreur Sqlite3
+    pub fn set_trash_days(&self, days: i64) -> Result<()> {
+        let conn = self.connect()?;
+
+        conn.execute( "INSERT INTO SETTING (key, value) VALUES ('trash_days', :days)
+            ON CONFLICT(key) DO UPDATE SET value = excluded.value", named_params!{ ":days": days } )?;
+        Ok(())
+    }
+
+    /// purge : supprime définitivement les entrées restées dans la corbeille plus longtemps que la
+    /// durée de conservation (tous profils confondus). Les suppressions restent dans l'historique.
+    /// - params : &self -> une référence sur la structure contenant l'url
+    /// - return : le nombre d'entrées supprimées en cas de succès sinon l'erreur Sqlite3
+    pub fn purge(&self) -> Result<usize> {
+        let days = self.trash_days()?;
+        let conn = self.connect()?;
+
+        let tx = conn.unchecked_transaction()?;
+        let last = CaDaoSqlite::last_history( &tx )?;
+        let nb = tx.execute( "DELETE FROM ENTRY WHERE deleted_at IS NOT NULL
+            AND deleted_at < strftime('%Y-%m-%d %H:%M:%S', 'now', 'localtime', :delay)",
+            named_params!{ ":delay": format!( "-{days} days" ) } )?;
+        self.sign_history( &tx, last )?;
+        tx.commit()?;
+        Ok(nb)
+    }
+
+    /// integrity_check : vérifie l'intégrité du fichier sqlite3 (PRAGMA integrity_check).
+    /// - params : &self -> une référence sur la structure contenant l'url
+    /// - return : un Vecteur contenant les problèmes trouvés par sqlite ("ok" si il n'y en a pas)
+    ///   en cas de succès sinon l'erreur Sqlite3
+    pub fn integrity_check(&self) -> Result<Vec<String>> {
+        let conn = self.connect()?;
+
+        let mut stmt = conn.prepare( "PRAGMA integrity_check" )?;
+        let rows = stmt.query_map( [], |r| r.get(0) )?;
+        let mut result = Vec::new();
+        for r in rows {
+            result.push( r? );
+        }
+        Ok(result)
+    }
+
+    /// anomalies : recherche les anomalies de toutes les entrées de la table ENTRY, tous profils
+    /// confondus et corbeille comprise (cf. fichier doctor.rs).
+    /// - params :
+    ///     - &self -> une référence sur la structure contenant l'url
+    ///     - today -> la date du jour, une entrée postérieure est une anomalie
+    /// - return : un Vecteur contenant les anomalies en cas de succès sinon l'erreur Sqlite3
+    pub fn anomalies(&self, today: NaiveDate) -> Result<Vec<Anomaly>> {
+        let conn = self.connect()?;
+
+        let mut stmt = conn.prepare( "SELECT id, profile_id, date, ca, hours, hsup FROM ENTRY ORDER BY date, id" )?;
+        let rows = stmt.query_map( [], |r| Ok( Anomaly::check_entry( r.get(0)?, r.get(1)?, &r.get(2)?, &r.get(3)?,
+            &r.get(4)?, &r.get(5)?, today ) ) )?;
+        let mut anomalies = Vec::new();
+        for a in rows {
+            anomalies.extend( a? );
+        }
+        Ok(anomalies)
+    }
+
+    /// repair : répare les anomalies qui peuvent l'être, dans une seule transaction. Les tags
+    /// d'une date réparée sont déplacés sur la nouvelle date.
+    /// - params :
+    ///     - &self -> une référence sur la structure contenant l'url
+    ///     - anomalies -> les anomalies trouvées par la fonction anomalies
+    /// - return : le nombre d'anomalies réparées en cas de succès sinon l'erreur Sqlite3
+    pub fn repair(&self, anomalies: &[Anomaly]) -> Result<usize> {
+        let conn = self.connect()?;
+
+        let tx = conn.unchecked_transaction()?;
+        let last = CaDaoSqlite::last_history( &tx )?;
+        let mut nb = 0;
+        for a in anomalies.iter() {
+            let Some(repair) = &a.repair else { continue; };
+            match repair {
+                Repair::ZeroHours => tx.execute( "UPDATE ENTRY SET hours = 0 WHERE id = :id AND hours IS NULL",
+                    named_params!{ ":id": a.entry_id } )?,
+                Repair::Ca(ca) => tx.execute( "UPDATE ENTRY SET ca = :ca WHERE id = :id",
+                    named_params!{ ":id": a.entry_id, ":ca": ca } )?,
+                Repair::Date(d) => {
+                    tx.execute( "UPDATE OR REPLACE CA_TAG SET date = :new WHERE profile_id = :profile AND date = :old",
+                        named_params!{ ":profile": a.profile, ":old": a.date, ":new": d } )?;
+                    tx.execute( "UPDATE ENTRY SET date = :new WHERE id = :id", named_params!{ ":id": a.entry_id, ":new": d } )?
+                },
+            };
+            nb += 1;
+        }
+        self.sign_history( &tx, last )?;
+        tx.commit()?;
+        Ok(nb)
+    }
+
+    /// history_from_row : construit un objet History à partir d'une ligne de la table HISTORY.
+    /// - params : row -> la ligne retournée par une requête sur la table HISTORY
+    /// - return : un objet History en cas de succès sinon l'erreur Sqlite3
+    fn history_from_row(row: &Row) -> Result<History> {
+        let op: String = row.get("op")?;
+        let entry = |prefix: &str| -> Result<Entry> {
+            Ok(Entry {
+                id: row.get("entry_id")?,
+                activity: row.get::<_, Option<String>>( format!("{prefix}_activity").as_str() )?.unwrap_or_default(),
+                ca: row.get::<_, Option<f32>>( format!("{prefix}_ca").as_str() )?.unwrap_or_default(),
+                hours: row.get::<_, Option<f32>>( format!("{prefix}_hours").as_str() )?.unwrap_or_default(),
+                hsup: row.get::<_, Option<f32>>( format!("{prefix}_hsup").as_str() )?.unwrap_or_default(),
+                comment: row.get( format!("{prefix}_comment").as_str() )?,
+            })
+        };
+        Ok(History {
+            id: row.get("id")?,
+            entry_id: row.get("entry_id")?,
+            ts: row.get("ts")?,
+            source: row.get("source")?,
+            date: row.get("date")?,
+            old: if op == "add" { None } else { Some( entry("old")? ) },
+            new: if op == "delete" { None } else { Some( entry("new")? ) },
+            op,
+        })
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::profile::DEFAULT_PROFILE;
+
+    /// un fichier sqlite3 temporaire, supprimé à la fin du test
+    pub(crate) struct TempDb {
+        pub url: String,
+    }
+
+    impl TempDb {
+        /// new : un fichier propre au test (et au processus) dans le répertoire temporaire
+        pub(crate) fn new(name: &str) -> TempDb {
+            let path = std::env::temp_dir().join( format!( "fde-{name}-{}.sq3", std::process::id() ) );
+            let _ = std::fs::remove_file( &path );
+            TempDb{ url: path.to_string_lossy().to_string() }
+        }
+    }
+
+    impl Drop for TempDb {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file( &self.url );
+        }
+    }
+
+    /// une base en mémoire : la session garde la même connexion pour toutes les requêtes
+    fn memory() -> CaDaoSqlite {
+        CaDaoSqlite::new( ":memory:", DEFAULT_PROFILE ).open_session().unwrap()
+    }
+
+    /// date : une date du calendrier
+    pub(crate) fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt( y, m, d ).unwrap()
+    }
+
+    /// day : un jour du profil par défaut avec une entrée par triplet (ca, heures, heures sup.)
+    pub(crate) fn day(d: NaiveDate, values: &[(f32, f32, f32)]) -> Ca {
+        let entries = values.iter().map( |&(ca, hours, hsup)| Entry{ ca, hours, hsup, ..Entry::default() } ).collect();
+        let mut c = Ca{ profile: DEFAULT_PROFILE, date: d.to_string(), ca: 0.0, hours: 0.0, hsup: 0.0, comment: None,
+            tags: Vec::new(), entries };
+        c.total();
+        c
+    }
+
+    #[test]
+    fn search_lists_a_day_once() {
+        let sqlite = memory();
+        let mut c = day( date( 2024, 5, 2 ), &[(150.0, 7.0, 0.0), (50.0, 1.5, 0.0)] );
+        c.entries[0].comment = Some( "salon du livre".to_string() );
+        c.entries[1].comment = Some( "retour du salon".to_string() );
+        sqlite.add( &c ).unwrap();
+        let mut c = day( date( 2024, 5, 3 ), &[(80.0, 4.0, 0.0)] );
+        c.entries[0].comment = Some( "salon".to_string() );
+        sqlite.add( &c ).unwrap();
+
+        let found = sqlite.search( &[ "salon".to_string() ], "[", "]" ).unwrap();
+        assert_eq!( found.iter().map( |(c, _)| c.date.as_str() ).collect::<Vec<&str>>(), ["2024-05-02", "2024-05-03"] );
+        assert_eq!( found[0].1, "[salon] du livre / retour du [salon]" );
+    }
+
+    #[test]
+    fn add_get_update_delete() {
+        let sqlite = memory();
+        let d = date( 2024, 5, 2 );
+        assert!( !sqlite.exist( &d ).unwrap() );
+
+        sqlite.add( &day( d, &[(150.0, 7.0, 0.0), (50.0, 1.5, 0.5)] ) ).unwrap();
+        assert!( sqlite.exist( &d ).unwrap() );
+        let mut c = sqlite.get( &d ).unwrap();
+        assert_eq!( (c.ca, c.hours, c.hsup), (200.0, 8.5, 0.5) );
+        assert_eq!( c.entries.len(), 2 );
+        assert!( c.entries.iter().all( |e| e.id.is_some() ) );
+
+        c.entries.remove( 1 );
+        c.entries[0].ca = 120.0;
+        c.tags = vec![ "salon".to_string() ];
+        sqlite.update( &c ).unwrap();
+        let c = sqlite.get( &d ).unwrap();
+        assert_eq!( (c.ca, c.hours, c.entries.len()), (120.0, 7.0, 1) );
+        assert_eq!( c.tags, vec![ "salon".to_string() ] );
+        // l'activité retirée est dans la corbeille
+        assert_eq!( sqlite.trash().unwrap()[0].0.ca, 50.0 );
+
+        sqlite.del( c ).unwrap();
+        assert!( !sqlite.exist( &d ).unwrap() );
+        assert_eq!( sqlite.trash().unwrap().len(), 1 );
+        assert_eq!( sqlite.untrash( &d ).unwrap(), 2 );
+        assert_eq!( sqlite.get( &d ).unwrap().ca, 170.0 );
+    }
+
+    #[test]
+    fn month_aggregates() {
+        let sqlite = memory();
+        sqlite.add( &day( date( 2024, 5, 2 ), &[(150.0, 7.0, 0.0)] ) ).unwrap();
+        let mut c = day( date( 2024, 5, 3 ), &[(250.5, 9.0, 2.0)] );
+        c.tags = vec![ "salon".to_string() ];
+        sqlite.add( &c ).unwrap();
+        // ni le mois suivant, ni les jours effacés, ni les autres profils ne sont comptés
+        sqlite.add( &day( date( 2024, 6, 1 ), &[(1000.0, 8.0, 0.0)] ) ).unwrap();
+        sqlite.add( &day( date( 2024, 5, 4 ), &[(80.0, 2.0, 0.0)] ) ).unwrap();
+        sqlite.del( sqlite.get( &date( 2024, 5, 4 ) ).unwrap() ).unwrap();
+        let mut other = day( date( 2024, 5, 2 ), &[(999.0, 1.0, 0.0)] );
+        other.profile = 2;
+        sqlite.add( &other ).unwrap();
+
+        let d = date( 2024, 5, 15 );
+        assert_eq!( sqlite.ca_for_month( &d, &[] ).unwrap(), 400.5 );
+        assert_eq!( sqlite.hours_for_month( &d, &[] ).unwrap(), 16.0 );
+        assert_eq!( sqlite.hsup_for_month( &d, &[] ).unwrap(), 2.0 );
+        assert_eq!( sqlite.datas_for_month( &d, &[] ).unwrap().len(), 2 );
+
+        let tags = vec![ "salon".to_string() ];
+        assert_eq!( sqlite.ca_for_month( &d, &tags ).unwrap(), 250.5 );
+        assert_eq!( sqlite.datas_for_month( &d, &tags ).unwrap().len(), 1 );
+
+        let totals = sqlite.totals( &date( 2024, 5, 1 ), &date( 2024, 7, 1 ), Step::Month ).unwrap();
+        assert_eq!( totals.iter().map( |t| (t.start, t.ca, t.days) ).collect::<Vec<_>>(),
+            vec![ (date( 2024, 5, 1 ), 400.5, 2), (date( 2024, 6, 1 ), 1000.0, 1) ] );
+    }
+
+    #[test]
+    fn empty_month() {
+        let sqlite = memory();
+        let d = date( 2024, 2, 1 );
+        assert_eq!( sqlite.ca_for_month( &d, &[] ).unwrap(), 0.0 );
+        assert_eq!( sqlite.hours_for_month( &d, &[] ).unwrap(), 0.0 );
+        assert_eq!( sqlite.hsup_for_month( &d, &[] ).unwrap(), 0.0 );
+        assert!( sqlite.datas_for_month( &d, &[] ).unwrap().is_empty() );
+    }
+
+    #[test]
+    fn empty_period_totals() {
+        let sqlite = memory();
+        let year = Period::year( 2024 ).unwrap();
+        assert!( sqlite.totals( &year.start, &year.end(), Step::Month ).unwrap().is_empty() );
+        let s = sqlite.summary( &year, 3000.0 ).unwrap();
+        assert_eq!( (s.ca, s.hours, s.months, s.above), (0.0, 0.0, 0, 0) );
+    }
+
+    #[test]
+    fn temp_file_without_session() {
+        let db = TempDb::new( "cadaosqlite" );
+        // chaque requête ouvre sa propre connexion sur le fichier
+        let sqlite = CaDaoSqlite::new( &db.url, DEFAULT_PROFILE );
+        sqlite.add( &day( date( 2024, 5, 2 ), &[(150.0, 7.0, 0.0)] ) ).unwrap();
+        let sqlite = CaDaoSqlite::new( &db.url, DEFAULT_PROFILE );
+        assert_eq!( sqlite.get( &date( 2024, 5, 2 ) ).unwrap().ca, 150.0 );
+        assert_eq!( sqlite.history( &date( 2024, 5, 2 ) ).unwrap().len(), 1 );
+        assert!( schema::check( &Connection::open( &db.url ).unwrap() ).unwrap().is_empty() );
+    }
+
+    #[test]
+    fn profiles() {
+        let sqlite = memory();
+        let mut p = sqlite.profile( None ).unwrap();
+        assert_eq!( p.id, DEFAULT_PROFILE );
+        p.id = 0;
+        p.name = "Léa".to_string();
+        p.threshold = 2000.0;
+        sqlite.save_profile( &p ).unwrap();
+        let lea = sqlite.profile( Some( "Léa" ) ).unwrap();
+        assert_ne!( lea.id, DEFAULT_PROFILE );
+        assert_eq!( lea.threshold, 2000.0 );
+        assert!( matches!( sqlite.profile( Some( "personne" ) ), Err(rusqlite::Error::QueryReturnedNoRows) ) );
+    }
 }
@@ -0,0 +1,101 @@
+//! # calendar :
+//! Ce module regroupe le modèle de calendrier : les jours fériés français (fixes et mobiles,
+//! calculés à partir de la date de Pâques) et les jours de congé, reconnus grâce aux tags portés
+//! par une journée (cf. fichier tag.rs). Les noms des jours fériés et des sortes de congé sont des
+//! clés du catalogue des messages (cf. fichier i18n.rs).
+//! - author : Thierry Probst <thierry.probst@free.fr>
+//! - version : 1.0.0
+//! - date : 19/10/2026
+use chrono::{Days, NaiveDate};
+use crate::i18n;
+
+
+/// la sorte de congé des congés payés, les autres sortes sont précisées dans le calendrier
+pub const PAID_LEAVE: &str = "leave.paid";
+
+/// les tags qui font d'une journée un jour de congé (et non un jour travaillé) : (tag, sorte de
+/// congé)
+pub const LEAVE_TAGS: &[(&str, &str)] = &[
+    ("congé", PAID_LEAVE),
+    ("congés", PAID_LEAVE),
+    ("maladie", "leave.sick"),
+    ("rtt", "leave.rtt"),
+    ("absence", "leave.absence"),
+];
+
+/// les jours fériés fixes : (mois, jour, clé du nom)
+const FIXED_HOLIDAYS: &[(u32, u32, &str)] = &[
+    (1, 1, "holiday.new_year"),
+    (5, 1, "holiday.labour_day"),
+    (5, 8, "holiday.victory"),
+    (7, 14, "holiday.national"),
+    (8, 15, "holiday.assumption"),
+    (11, 1, "holiday.all_saints"),
+    (11, 11, "holiday.armistice"),
+    (12, 25, "holiday.christmas"),
+];
+
+/// easter : la date du dimanche de Pâques (algorithme de Meeus / Jones / Butcher)
+/// - params : year -> l'année
+/// - return : la date de Pâques
+pub fn easter( year: i32 ) -> NaiveDate {
+    let a = year % 19;
+    let b = year / 100;
+    let c = year % 100;
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+    NaiveDate::from_ymd_opt( year, month as u32, day as u32 ).unwrap()
+}
+
+/// holidays : les jours fériés d'une année
+/// - params : year -> l'année
+/// - return : un Vecteur contenant les jours fériés (date, nom dans la langue de l'interface)
+///   triés par date
+pub fn holidays( year: i32 ) -> Vec<(NaiveDate, String)> {
+    let mut v: Vec<(NaiveDate, &str)> = FIXED_HOLIDAYS.iter()
+        .filter_map( |(m, d, key)| NaiveDate::from_ymd_opt( year, *m, *d ).map( |date| (date, *key) ) )
+        .collect();
+    let e = easter( year );
+    v.push( (e + Days::new( 1 ), "holiday.easter_monday") );
+    v.push( (e + Days::new( 39 ), "holiday.ascension") );
+    v.push( (e + Days::new( 50 ), "holiday.whit_monday") );
+    v.sort();
+    v.into_iter().map( |(date, key)| (date, i18n::tr( key ).to_string()) ).collect()
+}
+
+/// leave : la sorte de congé d'une journée, d'après ses tags
+/// - params : tags -> les tags de la journée
+/// - return : la clé de la sorte de congé du premier tag de congé (cf. LEAVE_TAGS), None pour un
+///   jour travaillé
+pub fn leave( tags: &[String] ) -> Option<&'static str> {
+    tags.iter().find_map( |t| LEAVE_TAGS.iter().find( |(tag, _)| tag == t ).map( |(_, kind)| *kind ) )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn leave_kinds() {
+        let tags = |v: &[&str]| v.iter().map( |t| t.to_string() ).collect::<Vec<String>>();
+        assert_eq!( leave( &tags( &["salon", "congés"] ) ), Some( PAID_LEAVE ) );
+        assert_eq!( leave( &tags( &["maladie"] ) ), Some( "leave.sick" ) );
+        assert_eq!( leave( &tags( &["salon"] ) ), None );
+    }
+
+    #[test]
+    fn holidays_of_a_year() {
+        let v = holidays( 2024 );
+        assert_eq!( v.len(), 11 );
+        assert_eq!( v[1].0, NaiveDate::from_ymd_opt( 2024, 4, 1 ).unwrap() );
+    }
+}
@@ -5,13 +5,15 @@
 //! - date : 23/04/2023
 use crate::ca::Ca;
 use crate::cadaosqlite::CaDaoSqlite;
+use crate::profile::Profile;
+use crate::tag::TagTotal;
 use rusqlite::{Result};
-use chrono::NaiveDate;
-
-
+use chrono::{Datelike, Months, NaiveDate};
 
 #[derive(Debug)]
 pub struct CaMonth {
+    /// le profil concerné, qui définit aussi les règles de calcul de la prime
+    pub profile: Profile,
     ///le jour permettant de définir le mois
     pub day: NaiveDate,
     /// le chiffre d'affaire du mois
@@ -22,23 +24,122 @@ pub struct CaMonth {
     pub hsup: f64,
     /// le détail de chaque jour du mois
     pub datas: Vec<Ca>,
+    /// limite les données aux jours portant l'un de ces tags (si non vide)
+    pub tags: Vec<String>,
+    /// les totaux par tag du mois
+    pub tag_totals: Vec<TagTotal>,
 }
 
 
 impl CaMonth {
+    /// new : crée un CaMonth vide pour le mois contenant day
+    /// - params :
+    ///     - day -> un jour du mois concerné
+    ///     - profile -> le profil concerné
+    ///     - tags -> les tags auxquels limiter les données (vide pour tout le mois)
+    /// - return : un objet CaMonth
+    pub fn new( day: NaiveDate, profile: &Profile, tags: &[String] ) -> CaMonth {
+        CaMonth{ profile: profile.clone(), day, ca: 0.0, hours: 0.0, hsup: 0.0, datas: Vec::new(), tags: tags.to_vec(), tag_totals: Vec::new() }
+    }
+
     /// récupère les données du mois et complète la structure CaMonth
     /// - params :
     ///     - self -> la strucure concernée
     ///     - url -> un objet String représentant l'url du fichier sqlite3
     /// - return : uniquement les erreurs sqlite3 éventuelles via une structure Result
     pub fn retrieve_datas(&mut self, url: String) -> Result<()> {
-        let sqlite = CaDaoSqlite{ url: url.to_string() };
+        self.retrieve_with( &CaDaoSqlite::new( &url, self.profile.id ) )
+    }
 
-        self.ca = sqlite.ca_for_month( &self.day )?;
-        self.hours = sqlite.hours_for_month( &self.day )?;
-        self.hsup = sqlite.hsup_for_month( &self.day )?;
-        self.datas = sqlite.datas_for_month( &self.day )?;
+    /// retrieve_with : comme retrieve_datas, avec un CaDaoSqlite existant (cf.
+    /// CaDaoSqlite::open_session)
+    /// - params :
+    ///     - self -> la strucure concernée
+    ///     - sqlite -> le CaDaoSqlite à utiliser
+    /// - return : uniquement les erreurs sqlite3 éventuelles via une structure Result
+    pub fn retrieve_with(&mut self, sqlite: &CaDaoSqlite) -> Result<()> {
+        self.ca = sqlite.ca_for_month( &self.day, &self.tags )?;
+        self.hours = sqlite.hours_for_month( &self.day, &self.tags )?;
+        self.hsup = sqlite.hsup_for_month( &self.day, &self.tags )?;
+        self.datas = sqlite.datas_for_month( &self.day, &self.tags )?;
+        let start = self.day.with_day( 1 ).unwrap();
+        self.tag_totals = sqlite.tag_totals( &start, &(start + Months::new( 1 )), &self.tags )?;
         Ok(())
     }
 
+    /// delta : l'écart entre le chiffre d'affaire du mois et le seuil de la prime du profil
+    /// - params : self -> la strucure concernée
+    /// - return : un float, positif si le seuil est dépassé
+    pub fn delta(&self) -> f64 {
+        self.profile.delta( self.ca )
+    }
+
+    /// prime : le montant de la prime due pour le mois
+    /// - params : self -> la strucure concernée
+    /// - return : un float, nul si le seuil n'est pas dépassé
+    pub fn prime(&self) -> f64 {
+        self.profile.prime( self.ca )
+    }
+
+    /// worked_days : le nombre de jours travaillés dans le mois (jours avec des heures ou du
+    /// chiffre d'affaire)
+    /// - params : self -> la strucure concernée
+    /// - return : le nombre de jours
+    pub fn worked_days(&self) -> usize {
+        self.datas.iter().filter( |c| c.hours > 0.0 || c.ca > 0.0 ).count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cadaosqlite::tests::{date, day, TempDb};
+    use crate::profile::DEFAULT_PROFILE;
+
+    #[test]
+    fn retrieve_datas() {
+        let db = TempDb::new( "camonth" );
+        let sqlite = CaDaoSqlite::new( &db.url, DEFAULT_PROFILE );
+        sqlite.add( &day( date( 2024, 5, 2 ), &[(2000.0, 7.0, 0.0)] ) ).unwrap();
+        sqlite.add( &day( date( 2024, 5, 3 ), &[(1500.0, 8.0, 1.0), (500.0, 1.0, 0.0)] ) ).unwrap();
+        sqlite.add( &day( date( 2024, 5, 4 ), &[(0.0, 0.0, 0.0)] ) ).unwrap();
+        let profile = sqlite.profile( None ).unwrap();
+
+        let mut m = CaMonth::new( date( 2024, 5, 20 ), &profile, &[] );
+        m.retrieve_datas( db.url.clone() ).unwrap();
+        assert_eq!( (m.ca, m.hours, m.hsup), (4000.0, 16.0, 1.0) );
+        assert_eq!( m.datas.len(), 3 );
+        assert_eq!( m.worked_days(), 2 );
+        assert!( (m.delta() - (4000.0 - profile.threshold)).abs() < 1e-9 );
+        assert!( (m.prime() - 4000.0 * profile.rate).abs() < 1e-9 );
+    }
+
+    #[test]
+    fn no_prime_below_threshold() {
+        let db = TempDb::new( "camonth-below" );
+        let sqlite = CaDaoSqlite::new( &db.url, DEFAULT_PROFILE );
+        sqlite.add( &day( date( 2024, 5, 2 ), &[(1000.0, 7.0, 0.0)] ) ).unwrap();
+        let profile = sqlite.profile( None ).unwrap();
+
+        let mut m = CaMonth::new( date( 2024, 5, 1 ), &profile, &[] );
+        m.retrieve_with( &sqlite ).unwrap();
+        assert_eq!( m.ca, 1000.0 );
+        assert!( m.delta() < 0.0 );
+        assert_eq!( m.prime(), 0.0 );
+    }
+
+    #[test]
+    fn empty_month() {
+        let db = TempDb::new( "camonth-empty" );
+        let sqlite = CaDaoSqlite::new( &db.url, DEFAULT_PROFILE );
+        sqlite.add( &day( date( 2024, 5, 2 ), &[(1000.0, 7.0, 0.0)] ) ).unwrap();
+        let profile = sqlite.profile( None ).unwrap();
+
+        let mut m = CaMonth::new( date( 2024, 6, 1 ), &profile, &[] );
+        m.retrieve_datas( db.url.clone() ).unwrap();
+        assert_eq!( (m.ca, m.hours, m.hsup), (0.0, 0.0, 0.0) );
+        assert!( m.datas.is_empty() && m.tag_totals.is_empty() );
+        assert_eq!( m.worked_days(), 0 );
+        assert_eq!( m.prime(), 0.0 );
+    }
 }
@@ -0,0 +1,76 @@
+//! # caperiod :
+//! Ce module définit un CaPeriod représentant l'activité cumulée sur une période de plusieurs
+//! mois. Les valeurs sont obtenues en additionnant celles de chacun des CaMonth de la période.
+//! - author : Thierry Probst <thierry.probst@free.fr>
+//! - version : 1.0.0
+//! - date : 19/10/2026
+use crate::camonth::CaMonth;
+use crate::period::Period;
+use crate::cadaosqlite::CaDaoSqlite;
+use crate::profile::Profile;
+use crate::tag::TagTotal;
+use rusqlite::Result;
+
+
+#[derive(Debug)]
+pub struct CaPeriod {
+    /// le profil concerné
+    pub profile: Profile,
+    /// la période concernée
+    pub period: Period,
+    /// le chiffre d'affaire de la période
+    pub ca: f64,
+    /// le nombre d'heures
+    pub hours: f64,
+    /// le nombre d'heures supplémentaires
+    pub hsup: f64,
+    /// le nombre de jours travaillés
+    pub days: usize,
+    /// le cumul des primes mensuelles
+    pub prime: f64,
+    /// limite les données aux jours portant l'un de ces tags (si non vide)
+    pub tags: Vec<String>,
+    /// les totaux par tag de la période
+    pub tag_totals: Vec<TagTotal>,
+}
+
+
+impl CaPeriod {
+    /// new : crée un CaPeriod vide pour la période indiquée
+    /// - params :
+    ///     - period -> la période concernée
+    ///     - profile -> le profil concerné
+    ///     - tags -> les tags auxquels limiter les données (vide pour toute la période)
+    /// - return : un objet CaPeriod
+    pub fn new( period: Period, profile: &Profile, tags: &[String] ) -> CaPeriod {
+        CaPeriod{ profile: profile.clone(), period, ca: 0.0, hours: 0.0, hsup: 0.0, days: 0, prime: 0.0, tags: tags.to_vec(),
+            tag_totals: Vec::new() }
+    }
+
+    /// récupère les données de chacun des mois de la période et les cumule
+    /// - params :
+    ///     - self -> la strucure concernée
+    ///     - url -> un objet String représentant l'url du fichier sqlite3
+    /// - return : uniquement les erreurs sqlite3 éventuelles via une structure Result
+    pub fn retrieve_datas(&mut self, url: String) -> Result<()> {
+        for day in self.period.months() {
+            let mut cmonth = CaMonth::new( day, &self.profile, &self.tags );
+            cmonth.retrieve_datas( url.to_string() )?;
+            self.ca += cmonth.ca;
+            self.hours += cmonth.hours;
+            self.hsup += cmonth.hsup;
+            self.days += cmonth.worked_days();
+            self.prime += cmonth.prime();
+        }
+        let sqlite = CaDaoSqlite::new( &url, self.profile.id );
+        self.tag_totals = sqlite.tag_totals( &self.period.start, &self.period.end(), &self.tags )?;
+        Ok(())
+    }
+
+    /// ca_per_hour : le chiffre d'affaire moyen par heure travaillée
+    /// - params : self -> la strucure concernée
+    /// - return : un float, nul si aucune heure n'a été travaillée
+    pub fn ca_per_hour(&self) -> f64 {
+        if self.hours > 0.0 { self.ca / self.hours } else { 0.0 }
+    }
+}
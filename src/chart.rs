@@ -0,0 +1,96 @@
+//! # chart :
+//! Ce module dessine des graphiques dans le terminal avec des caractères Unicode : des barres
+//! horizontales (une ligne par jour, par semaine ou par mois) et des sparklines (une série de
+//! valeurs résumée sur une seule ligne). Les totaux sont calculés par CaDaoSqlite::totals.
+//! - author : Thierry Probst <thierry.probst@free.fr>
+//! - version : 1.0.0
+//! - date : 19/10/2026
+use chrono::Datelike;
+use crate::i18n::{self, msg};
+use crate::period::PeriodTotal;
+
+
+/// la longueur (en caractères) de la plus longue barre
+const WIDTH: usize = 50;
+/// les huitièmes de caractère qui terminent une barre
+const EIGHTHS: [char; 8] = [ ' ', '▏', '▎', '▍', '▌', '▋', '▊', '▉' ];
+/// les hauteurs des caractères d'une sparkline
+const SPARKS: [char; 8] = [ '▁', '▂', '▃', '▄', '▅', '▆', '▇', '█' ];
+/// le repère du seuil de la prime, tracé derrière les barres
+const MARKER: char = '┊';
+
+/// sparkline : résume une série de valeurs sur une ligne, une valeur nulle est laissée vide
+/// - params : values -> les valeurs
+/// - return : une chaine contenant un caractère par valeur
+pub fn sparkline( values: &[f64] ) -> String {
+    let max = values.iter().cloned().fold( 0.0, f64::max );
+    values.iter().map( |v| match *v > 0.0 {
+        true => SPARKS[ ((v / max * 7.0).round() as usize).min( 7 ) ],
+        false => ' ',
+    }).collect()
+}
+
+/// bar : une barre horizontale, précise au huitième de caractère
+/// - params : value, max -> la valeur représentée et la valeur de la barre la plus longue
+/// - return : la barre, complétée par des espaces jusqu'à WIDTH + 1 caractères
+pub fn bar( value: f64, max: f64 ) -> String {
+    let eighths = if max > 0.0 && value > 0.0 { (value / max * (WIDTH * 8) as f64).round() as usize } else { 0 };
+    let mut s = "█".repeat( eighths / 8 );
+    if eighths % 8 > 0 { s.push( EIGHTHS[ eighths % 8 ] ); }
+    format!( "{s:<width$}", width = WIDTH + 1 )
+}
+
+/// bars : un graphique en barres horizontales, une ligne par valeur
+/// - params :
+///     - rows -> les lignes (libellé, valeur, valeur affichée après la barre)
+///     - threshold -> la valeur repérée par une ligne verticale (optionnelle)
+/// - return : les lignes du graphique
+pub fn bars( rows: &[(String, f64, String)], threshold: Option<f64> ) -> Vec<String> {
+    let max = rows.iter().map( |r| r.1 ).chain( threshold ).fold( 0.0, f64::max );
+    let marker = threshold.filter( |_| max > 0.0 ).map( |t| (t / max * WIDTH as f64).round() as usize );
+    rows.iter().map( |(label, value, text)| {
+        let mut b: Vec<char> = bar( *value, max ).chars().collect();
+        if let Some(m) = marker {
+            if b[m] == ' ' { b[m] = MARKER; }
+        }
+        format!( "{label} │{} {text}", b.into_iter().collect::<String>() )
+    }).collect()
+}
+
+/// days : le chiffre d'affaire de chaque jour
+/// - params : totals -> les totaux par jour (cf. Step::fill)
+/// - return : le graphique
+pub fn days( totals: &[PeriodTotal] ) -> String {
+    let rows: Vec<(String, f64, String)> = totals.iter()
+        .map( |t| (i18n::day_month( &t.start ), t.ca, format!( "{:>8} €", i18n::number( t.ca, 2 ) )) ).collect();
+    let mut lines = bars( &rows, None );
+    lines.push( format!( "{} : {}", msg!( "label.ca" ), sparkline( &totals.iter().map( |t| t.ca ).collect::<Vec<f64>>() ) ) );
+    lines.join( "\n" )
+}
+
+/// months : le chiffre d'affaire de chaque mois, comparé au seuil de la prime
+/// - params :
+///     - totals -> les totaux par mois (cf. Step::fill)
+///     - threshold -> le seuil mensuel de la prime
+/// - return : le graphique
+pub fn months( totals: &[PeriodTotal], threshold: f64 ) -> String {
+    let rows: Vec<(String, f64, String)> = totals.iter()
+        .map( |t| (i18n::month( &t.start ), t.ca, format!( "{:>9} € ({} {})", i18n::number( t.ca, 2 ), t.days, msg!( "unit.days" ) )) ).collect();
+    let mut lines = bars( &rows, Some( threshold ) );
+    lines.push( format!( "{MARKER} {}", msg!( "chart.threshold", i18n::number( threshold, 2 ),
+        totals.iter().filter( |t| t.ca > threshold ).count(), totals.len() ) ) );
+    lines.push( format!( "{} : {}", msg!( "label.ca" ), sparkline( &totals.iter().map( |t| t.ca ).collect::<Vec<f64>>() ) ) );
+    lines.join( "\n" )
+}
+
+/// weeks : le nombre d'heures de chaque semaine
+/// - params : totals -> les totaux par semaine (cf. Step::fill)
+/// - return : le graphique
+pub fn weeks( totals: &[PeriodTotal] ) -> String {
+    let rows: Vec<(String, f64, String)> = totals.iter()
+        .map( |t| (format!( "{} {}", msg!( "chart.week_no", format!( "{:02}", t.start.iso_week().week() ) ), i18n::day_month( &t.start ) ), t.hours,
+            msg!( "chart.week", format!( "{:>5}", i18n::number( t.hours, 1 ) ), i18n::number( t.hsup, 1 ) )) ).collect();
+    let mut lines = bars( &rows, None );
+    lines.push( format!( "{} : {}", msg!( "label.hours" ), sparkline( &totals.iter().map( |t| t.hours ).collect::<Vec<f64>>() ) ) );
+    lines.join( "\n" )
+}
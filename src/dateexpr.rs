@@ -0,0 +1,114 @@
+//! # dateexpr :
+//! Ce module analyse les dates saisies sur la ligne de commande. En plus du format dd/mm/yyyy,
+//! une date peut s'écrire :
+//! - today, aujourd'hui, yesterday ou hier
+//! - -n -> il y a n jours (-1 pour hier)
+//! - lundi, ..., dimanche (ou monday, ..., sunday) -> le dernier jour de ce nom, aujourd'hui
+//!   compris
+//! - dd/mm -> ce jour dans l'année en cours
+//! - yyyy-mm-dd -> le format ISO
+//!
+//! Les mois (mm/yyyy), trimestres et années (yyyy) sont des périodes, cf. Period::parse.
+//! - author : Thierry Probst <thierry.probst@free.fr>
+//! - version : 1.0.0
+//! - date : 19/10/2026
+use chrono::{Datelike, Days, Local, NaiveDate, Weekday};
+
+
+/// les noms des jours de la semaine, en français et en anglais
+const WEEKDAYS: [(&str, &str, Weekday); 7] = [
+    ("lundi", "monday", Weekday::Mon),
+    ("mardi", "tuesday", Weekday::Tue),
+    ("mercredi", "wednesday", Weekday::Wed),
+    ("jeudi", "thursday", Weekday::Thu),
+    ("vendredi", "friday", Weekday::Fri),
+    ("samedi", "saturday", Weekday::Sat),
+    ("dimanche", "sunday", Weekday::Sun),
+];
+
+/// today : la date du jour
+/// - params : aucun
+/// - return : une date
+pub fn today() -> NaiveDate {
+    Local::now().date_naive()
+}
+
+/// parse : analyse une expression de date par rapport à la date du jour (cf. parse_from)
+/// - params : s -> l'expression à analyser
+/// - return : la date trouvée, None si l'expression n'est pas reconnue
+pub fn parse( s: &str ) -> Option<NaiveDate> {
+    parse_from( s, today() )
+}
+
+/// parse_from : analyse une expression de date (cf. en-tête du module)
+/// - params :
+///     - s -> l'expression à analyser (les majuscules sont ignorées)
+///     - today -> la date à partir de laquelle sont calculées les dates relatives
+/// - return : la date trouvée, None si l'expression n'est pas reconnue
+pub fn parse_from( s: &str, today: NaiveDate ) -> Option<NaiveDate> {
+    let s = s.trim().to_lowercase();
+    match s.as_str() {
+        "today" | "aujourd'hui" => return Some( today ),
+        "yesterday" | "hier" => return today.checked_sub_days( Days::new( 1 ) ),
+        _ => (),
+    }
+    if let Some(n) = s.strip_prefix('-').and_then( |n| n.parse::<u64>().ok() ) {
+        return today.checked_sub_days( Days::new( n ) );
+    }
+    if let Some((_, _, w)) = WEEKDAYS.iter().find( |(fr, en, _)| s == *fr || s == *en ) {
+        let back = (today.weekday().num_days_from_monday() + 7 - w.num_days_from_monday()) % 7;
+        return today.checked_sub_days( Days::new( back as u64 ) );
+    }
+    match s.split('/').collect::<Vec<&str>>().as_slice() {
+        [d, m] => NaiveDate::from_ymd_opt( today.year(), m.parse().ok()?, d.parse().ok()? ),
+        [_, _, _] => NaiveDate::parse_from_str( &s, "%d/%m/%Y" ).ok(),
+        _ => NaiveDate::parse_from_str( &s, "%Y-%m-%d" ).ok(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// un mercredi
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt( 2024, 5, 15 ).unwrap()
+    }
+
+    fn date(y: i32, m: u32, d: u32) -> Option<NaiveDate> {
+        NaiveDate::from_ymd_opt( y, m, d )
+    }
+
+    #[test]
+    fn relative_days() {
+        assert_eq!( parse_from( "today", today() ), date( 2024, 5, 15 ) );
+        assert_eq!( parse_from( "Aujourd'hui", today() ), date( 2024, 5, 15 ) );
+        assert_eq!( parse_from( "hier", today() ), date( 2024, 5, 14 ) );
+        assert_eq!( parse_from( "yesterday", today() ), date( 2024, 5, 14 ) );
+        assert_eq!( parse_from( "-0", today() ), date( 2024, 5, 15 ) );
+        assert_eq!( parse_from( "-15", today() ), date( 2024, 4, 30 ) );
+    }
+
+    #[test]
+    fn weekdays() {
+        assert_eq!( parse_from( "mercredi", today() ), date( 2024, 5, 15 ) );
+        assert_eq!( parse_from( "lundi", today() ), date( 2024, 5, 13 ) );
+        assert_eq!( parse_from( "Thursday", today() ), date( 2024, 5, 9 ) );
+        assert_eq!( parse_from( "dimanche", today() ), date( 2024, 5, 12 ) );
+    }
+
+    #[test]
+    fn absolute_dates() {
+        assert_eq!( parse_from( "02/05/2024", today() ), date( 2024, 5, 2 ) );
+        assert_eq!( parse_from( " 2/5 ", today() ), date( 2024, 5, 2 ) );
+        assert_eq!( parse_from( "2023-12-31", today() ), date( 2023, 12, 31 ) );
+        assert_eq!( parse_from( "29/02/2024", today() ), date( 2024, 2, 29 ) );
+    }
+
+    #[test]
+    fn invalid() {
+        for s in ["", "demain", "31/02/2024", "13/2024", "32/05", "2024-13-01", "-x", "05/2024"] {
+            assert_eq!( parse_from( s, today() ), None, "{s}" );
+        }
+    }
+}
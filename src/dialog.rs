@@ -4,15 +4,27 @@
 //! - author : Thierry Probst <thierry.probst@free.fr>
 //! - version : 1.0.0
 //! - date : 23/04/2023
-use rusqlite::Error;
 use std::io::{self, Write};
+use chrono::{Datelike, Months, NaiveDate};
 
-use crate::AUTHOR;
-use crate::VERSION;
-use crate::VDATE;
-use crate::ca::Ca;
-use crate::camonth::CaMonth;
+use crate::{AUTHOR, VERSION, VDATE};
+use fde::ca::Ca;
+use fde::entry::Entry;
+use fde::doctor::Anomaly;
+use fde::error::Result;
+use fde::history::History;
+use fde::i18n::{self, msg};
+use fde::camonth::CaMonth;
+use fde::caperiod::CaPeriod;
+use fde::pay::{Pay, Payslip, Reconciliation};
+use fde::period::Period;
+use fde::profile::Profile;
+use fde::summary::Summary;
+use fde::tag::{self, TagTotal};
 
+/// les séquences ANSI encadrant les termes trouvés par une recherche
+pub const HIGHLIGHT_START: &str = "\x1b[1;33m";
+pub const HIGHLIGHT_END: &str = "\x1b[0m";
 
 #[derive(Debug)]
 pub struct Dialog {}
@@ -23,128 +35,400 @@ impl Dialog {
     /// la date.
     /// - params : aucun
     /// - return : aucun
-    pub fn show_welcome() -> () {
-        println!( "
-        fde est un programe permettant de suivre l'activité de Nat.
-        {} v{} du {}
-        ", AUTHOR, VERSION, VDATE );
+    pub fn show_welcome() {
+        println!( "{}", msg!( "welcome", AUTHOR, VERSION, VDATE ) );
     }
 
     /// show_help : affichage d'une page d'aide comprenant l'utilisation de la ligne de commande.
     /// - params : aucun
     /// - return : aucun
-    pub fn show_help() -> () {
-        let help: &'static str = "
-        Usage : ./fde [option]
-        Options :
-        -h
-        --help          : cette aide.
-
-        -m=date
-        --month=date    : spécifie le mois qui sera affiché. le format de date
-        use std::io::{self, Write};                  est dd/mm/yyyy.
-
-        -d=date
-        --day=date    : spécifie le jour qui sera affiché en vue de sa création, modification
-                        ou suppression. le format de date est dd/mm/yyyy.
-        ";
-        println!("{help}");
-    }
-
-    /// something_goes_wrong : standardisation de l'affichage d'un message d'erreur.
-    /// - params :
-    ///     - fname -> une chaine spécifiant la fonction dans laquelle l'erreur a eu lieu
-    ///     - e -> l'erreur proprement dite
-    /// return : aucun
-    pub fn something_goes_wrong(fname: &str, e: Error ) -> () {
-        println!("Quelque chose s'est mal passé dans {fname}: {e}");
+    pub fn show_help() {
+        println!( "{}", msg!( "help" ) );
     }
 
     /// display_curmonth_data : affichage des données relatives à un mois particulier.
     /// - params : v -> un Vecteur contenant des objets Ca pour chacun des jours du mois
     /// - return : aucun
-    pub fn display_curmonth_data( v: &Vec<Ca> ) -> () {
+    pub fn display_curmonth_data( v: &[Ca] ) {
         for c in v.iter() {
             match &c.comment {
-                Some(x) => println!("\t{} : {:>4} / {:2} ({}) => {:?}", c.date, c.ca, c.hours, c.hsup, x ),
-                None => println!("\t{} : {:>4} / {:2} ({})", c.date, c.ca, c.hours, c.hsup ),
+                Some(x) => println!( "\t{} : {:>4} / {:2} ({}) => {:?}", i18n::iso_date( &c.date ), i18n::decimal( c.ca ),
+                    i18n::decimal( c.hours ), i18n::decimal( c.hsup ), x ),
+                None => println!( "\t{} : {:>4} / {:2} ({})", i18n::iso_date( &c.date ), i18n::decimal( c.ca ),
+                    i18n::decimal( c.hours ), i18n::decimal( c.hsup ) ),
             }
         }
     }
 
+    /// display_tag_totals : affichage des totaux par tag.
+    /// - params : v -> un Vecteur contenant les totaux de chacun des tags
+    /// - return : aucun
+    pub fn display_tag_totals( v: &[TagTotal] ) {
+        if v.is_empty() { return; }
+        println!( "{}", msg!( "tags.title" ) );
+        for t in v.iter() {
+            println!( "\t{:<12} : {:>8} / {:>5} h ({} {})", t.tag, i18n::number( t.ca, 2 ), i18n::decimal( t.hours ), t.days,
+                msg!( "unit.days" ) );
+        }
+    }
+
     /// show_month : affichage d'un rapport relatif à un mois particulier.
     /// ce rapport contient le chiffre d'affaire global, le nombre d'heures et d'heures supplémentaires
     /// ainsi les valeurs relatives à la prime (Delta par rapport au seuil, montant)
     /// - params : m -> un objet CaMonth contenant toutes les données nécessaires
     /// - return : aucun
-    pub fn show_month( m: CaMonth ) -> () {
-        let delta = m.ca - 3421.15;
-        let mut prime = 0.0;
-        if delta > 0.0 { prime = m.ca * 0.02; }
-        println!( "--- Valeurs pour le mois {0} ---
-        CA\t = {1:>7.2}
-        Hours\t = {2:>7}
-        HSup\t = {3:>7}
-        Delta\t = {4:>7.2}
-        Prime\t = {5:>7.2}" ,
-            m.day.format("%m-%Y").to_string(),
-            m.ca,
-            m.hours,
-            m.hsup,
-            delta,
-            prime );
-        println!("--- Données ------------------------");
+    pub fn show_month( m: CaMonth ) {
+        if !m.tags.is_empty() { println!( "{}", msg!( "filter", m.tags.join(", ") ) ); }
+        println!( "{}", msg!( "month.title", i18n::month( &m.day ), m.profile.name ) );
+        for (label, value) in [ ("label.ca", i18n::number( m.ca, 2 )), ("label.hours", i18n::decimal( m.hours )),
+            ("label.hsup", i18n::decimal( m.hsup )), ("label.delta", i18n::number( m.delta(), 2 )),
+            ("label.prime", i18n::number( m.prime(), 2 )) ] {
+            println!( "\t{:<8} = {:>8}", msg!( label ), value );
+        }
+        println!( "{}", msg!( "month.datas" ) );
+        if m.datas.is_empty() {
+            println!( "\t{}", msg!( "month.no_data" ) );
+            Dialog::display_calendar( &m.day );
+        }
         Dialog::display_curmonth_data( &m.datas );
+        Dialog::display_tag_totals( &m.tag_totals );
+    }
+
+    /// display_calendar : affichage des jours d'un mois, une ligne par semaine (du lundi au
+    /// dimanche)
+    /// - params : day -> un jour du mois concerné
+    /// - return : aucun
+    fn display_calendar( day: &NaiveDate ) {
+        let first = day.with_day( 1 ).unwrap();
+        let offset = first.weekday().num_days_from_monday() as usize;
+        let nb_days = ((first + Months::new( 1 )) - first).num_days() as usize;
+        let header: String = msg!( "weekdays" ).split(',').map( |d| format!( "{d:>4}" ) ).collect();
+        println!( "\t{}", &header[1..] );
+        let mut line = "    ".repeat( offset );
+        for i in 1..=nb_days {
+            line.push_str( &format!( "{i:>4}" ) );
+            if (offset + i).is_multiple_of( 7 ) || i == nb_days {
+                println!( "\t{}", &line[1..] );
+                line.clear();
+            }
+        }
+    }
+
+    /// show_compare : affichage côte à côte des valeurs de deux périodes, avec l'écart absolu et
+    /// l'écart en pourcentage de la seconde période par rapport à la première.
+    /// - params :
+    ///     - a -> la période de référence
+    ///     - b -> la période comparée
+    /// - return : aucun
+    pub fn show_compare( a: &CaPeriod, b: &CaPeriod ) {
+        if !a.tags.is_empty() { println!( "{}", msg!( "filter", a.tags.join(", ") ) ); }
+        println!( "{}", msg!( "compare.title", a.period, b.period, a.profile.name ) );
+        println!( "\t{:<10} {:>10} {:>10} {:>10} {:>8}", "", a.period.to_string(), b.period.to_string(),
+            msg!( "label.gap" ), "%" );
+        Dialog::display_compare_line( &msg!( "label.ca" ), a.ca, b.ca );
+        Dialog::display_compare_line( &msg!( "label.hours" ), a.hours, b.hours );
+        Dialog::display_compare_line( &msg!( "label.hsup" ), a.hsup, b.hsup );
+        Dialog::display_compare_line( &msg!( "label.ca_hour" ), a.ca_per_hour(), b.ca_per_hour() );
+        Dialog::display_compare_line( &msg!( "label.days" ), a.days as f64, b.days as f64 );
+        Dialog::display_compare_line( &msg!( "label.prime" ), a.prime, b.prime );
+        let mut tags: Vec<&String> = a.tag_totals.iter().chain( b.tag_totals.iter() ).map( |t| &t.tag ).collect();
+        tags.sort();
+        tags.dedup();
+        if !tags.is_empty() { println!( "{}", msg!( "tags.title" ) ); }
+        for t in tags {
+            let ta = a.tag_totals.iter().find( |x| &x.tag == t );
+            let tb = b.tag_totals.iter().find( |x| &x.tag == t );
+            Dialog::display_compare_line( &format!( "{t} {}", msg!( "label.ca" ) ), ta.map_or( 0.0, |x| x.ca ),
+                tb.map_or( 0.0, |x| x.ca ) );
+            Dialog::display_compare_line( &format!( "{t} h" ), ta.map_or( 0.0, |x| x.hours ), tb.map_or( 0.0, |x| x.hours ) );
+        }
+    }
+
+    /// display_compare_line : affichage d'une ligne du tableau de comparaison.
+    /// - params :
+    ///     - label -> le libellé de la ligne
+    ///     - a, b -> les valeurs des deux périodes
+    /// - return : aucun
+    fn display_compare_line( label: &str, a: f64, b: f64 ) {
+        let pct = if a != 0.0 { format!( "{:>7}%", i18n::signed( (b - a) / a * 100.0, 1 ) ) } else { format!( "{:>8}", "-" ) };
+        println!( "\t{:<10} {:>10} {:>10} {:>10} {}", label, i18n::number( a, 2 ), i18n::number( b, 2 ), i18n::signed( b - a, 2 ),
+            pct );
+    }
 
+    /// show_search : affichage des résultats d'une recherche dans les commentaires.
+    /// - params :
+    ///     - terms -> les termes recherchés
+    ///     - v -> les Ca trouvés accompagnés de l'extrait de leur commentaire
+    /// - return : aucun
+    pub fn show_search( terms: &[String], v: &[(Ca, String)] ) {
+        println!( "{}", msg!( "search.title", terms.join(" "), v.len() ) );
+        for (c, snippet) in v.iter() {
+            println!( "\t{} : {:>4} => {}", i18n::iso_date( &c.date ), i18n::decimal( c.ca ), snippet );
+        }
+    }
+
+    /// show_profiles : affichage de la liste des profils et de leurs règles de prime.
+    /// - params : v -> un Vecteur contenant les profils
+    /// - return : aucun
+    pub fn show_profiles( v: &[Profile] ) {
+        println!( "{}", msg!( "profiles.title" ) );
+        for p in v.iter() {
+            println!( "\t{p}" );
+        }
+    }
+
+    /// show_history : affichage de l'historique des modifications d'un jour.
+    /// - params :
+    ///     - d -> le jour concerné
+    ///     - v -> un Vecteur contenant les modifications
+    /// - return : aucun
+    pub fn show_history( d: &NaiveDate, v: &[History] ) {
+        println!( "{}", msg!( "history.title", i18n::date( d ) ) );
+        for h in v.iter() {
+            println!( "\t{h}" );
+        }
     }
 
-    /// menu_Ca : affiche un objet Ca, propose un menu pour modifier, sauvegarder, effacer
-    /// celui-ci en base de données, et renvoi la fonctionnalité choisie
+    /// show_trash : affichage des jours placés dans la corbeille.
+    /// - params :
+    ///     - v -> les Ca effacés accompagnés de la date de leur suppression
+    ///     - days -> la durée de conservation des jours effacés
+    /// - return : aucun
+    pub fn show_trash( v: &[(Ca, String)], days: i64 ) {
+        println!( "{}", msg!( "trash.title", v.len(), days ) );
+        for (c, deleted_at) in v.iter() {
+            println!( "\t{}", msg!( "trash.line", c, i18n::timestamp( deleted_at ) ) );
+        }
+    }
+
+    /// show_doctor : affichage du résultat de la vérification du fichier sqlite3.
+    /// - params :
+    ///     - integrity -> le résultat de PRAGMA integrity_check
+    ///     - v -> les anomalies trouvées dans les entrées
+    /// - return : aucun
+    pub fn show_doctor( integrity: &[String], v: &[Anomaly] ) {
+        println!( "{}", msg!( "doctor.integrity", integrity.join(", ") ) );
+        println!( "{}", msg!( "doctor.anomalies", v.len(), v.iter().filter( |a| a.repair.is_some() ).count() ) );
+        for a in v.iter() {
+            println!( "\t{a}" );
+        }
+    }
+
+    /// show_chart : affiche un graphique
+    /// - params : title, chart -> le titre et les lignes du graphique (cf. fichier chart.rs)
+    /// - return : aucun
+    pub fn show_chart( title: &str, chart: &str ) {
+        println!( "--- {title} ---" );
+        println!( "{chart}" );
+    }
+
+    /// show_pay : affichage de l'estimation de la paie d'un mois
+    /// - params :
+    ///     - m -> le mois concerné
+    ///     - pay -> l'estimation de la paie du mois
+    /// - return : aucun
+    pub fn show_pay( m: &CaMonth, pay: &Pay ) {
+        println!( "{}", msg!( "pay.title", i18n::month( &m.day ), m.profile.name ) );
+        println!( "\t{:<24} {:>7} h x {:>6} = {:>8}", msg!( "pay.normal" ), i18n::number( pay.hours, 2 ),
+            i18n::number( pay.wage, 2 ), i18n::number( pay.base(), 2 ) );
+        for (b, hours) in pay.overtime.iter() {
+            println!( "\t{:<24} {:>7} h x {:>6} = {:>8}", msg!( "pay.overtime", b ), i18n::number( *hours, 2 ),
+                i18n::number( pay.wage * (1.0 + b.rate), 2 ), i18n::number( b.amount( *hours, pay.wage ), 2 ) );
+        }
+        println!( "\t{:<24} {:>30}", msg!( "label.prime" ), i18n::number( pay.prime, 2 ) );
+        println!( "\t{:<24} {:>30}", msg!( "pay.gross" ), i18n::number( pay.gross(), 2 ) );
+    }
+
+    /// show_payslips : affichage des bulletins de paie enregistrés
+    /// - params :
+    ///     - name -> le nom du profil
+    ///     - v -> un Vecteur contenant les bulletins
+    /// - return : aucun
+    pub fn show_payslips( name: &str, v: &[Payslip] ) {
+        println!( "{}", msg!( "payslips.title", name, v.len() ) );
+        for p in v.iter() {
+            println!( "\t{p}" );
+        }
+    }
+
+    /// show_reconcile : affichage du rapprochement des bulletins de paie d'une période avec les
+    /// montants calculés, les mois présentant un écart sont signalés
+    /// - params :
+    ///     - period -> la période concernée
+    ///     - name -> le nom du profil
+    ///     - v -> un Vecteur contenant le rapprochement de chaque bulletin de la période
+    /// - return : aucun
+    pub fn show_reconcile( period: &Period, name: &str, v: &[Reconciliation] ) {
+        println!( "{}", msg!( "reconcile.title", period, name, v.iter().filter( |r| !r.flags().is_empty() ).count() ) );
+        println!( "\t{:<8} {:>10} {:>10} {:>10} {:>10} {:>10} {:>10}", msg!( "col.month" ), msg!( "col.prime" ),
+            msg!( "col.computed" ), msg!( "col.hsup" ), msg!( "col.computed" ), msg!( "col.gross" ), msg!( "col.estimated" ) );
+        for r in v.iter() {
+            println!( "\t{:<8} {:>10} {:>10} {:>10} {:>10} {:>10} {:>10}", i18n::month( &r.payslip.month ),
+                i18n::number( r.payslip.prime, 2 ), i18n::number( r.pay.prime, 2 ), i18n::number( r.payslip.overtime, 2 ),
+                i18n::number( r.pay.overtime_pay(), 2 ), i18n::number( r.payslip.gross, 2 ), i18n::number( r.pay.gross(), 2 ) );
+            for f in r.flags().iter() {
+                println!( "\t\t{HIGHLIGHT_START}=> {f}{HIGHLIGHT_END}" );
+            }
+        }
+    }
+
+    /// show_summary : affichage côte à côte des totaux et des moyennes mensuelles de plusieurs
+    /// périodes, avec le nombre de mois au dessus du seuil de la prime
+    /// - params :
+    ///     - profile -> le profil concerné
+    ///     - v -> les totaux de chacune des périodes
+    /// - return : aucun
+    pub fn show_summary( profile: &Profile, v: &[Summary] ) {
+        println!( "{}", msg!( "summary.title", profile.name, i18n::number( profile.threshold, 2 ) ) );
+        let line = |label: String, values: Vec<String>| println!( "\t{:<28} {}", label,
+            values.iter().map( |x| format!( "{x:>19}" ) ).collect::<Vec<String>>().join( " " ) );
+        line( String::new(), v.iter().map( |s| s.period.to_string() ).collect() );
+        line( msg!( "label.ca" ), v.iter().map( |s| i18n::number( s.ca, 2 ) ).collect() );
+        line( msg!( "summary.ca_avg" ), v.iter().map( |s| i18n::number( s.average( s.ca ), 2 ) ).collect() );
+        line( msg!( "label.hours" ), v.iter().map( |s| i18n::number( s.hours, 2 ) ).collect() );
+        line( msg!( "summary.hours_avg" ), v.iter().map( |s| i18n::number( s.average( s.hours ), 2 ) ).collect() );
+        line( msg!( "label.hsup" ), v.iter().map( |s| i18n::number( s.hsup, 2 ) ).collect() );
+        line( msg!( "summary.hsup_avg" ), v.iter().map( |s| i18n::number( s.average( s.hsup ), 2 ) ).collect() );
+        line( msg!( "summary.months" ), v.iter().map( |s| format!( "{} / {}", s.months, s.period.nb_months ) ).collect() );
+        line( msg!( "summary.above" ), v.iter().map( |s| s.above.to_string() ).collect() );
+    }
+
+    /// confirm_delete : demande la confirmation de l'effacement d'un Ca
+    /// - params : ca -> une référence sur le Ca à effacer
+    /// - return : true si l'utilisateur confirme, sinon l'erreur de lecture
+    pub fn confirm_delete( c: &Ca ) -> Result<bool> {
+        let answer = Dialog::ask( &msg!( "confirm.delete", i18n::iso_date( &c.date ) ) )?;
+        Ok( matches!( answer.to_lowercase().as_str(), "o" | "oui" | "y" | "yes" ) )
+    }
+
+    /// menu_Ca : affiche un objet Ca (et ses activités s'il en a plusieurs), propose un menu pour
+    /// modifier, sauvegarder, effacer celui-ci en base de données ou passer à un autre jour, et
+    /// renvoi la fonctionnalité choisie
     /// - params : ca -> une référence sur le Ca à afficher
     /// - return : une String contenant la valeur saisie par l'utilisateur
     pub fn menu_ca( c: &Ca ) -> String {
-        let choices = "[s]: sauvegarder, [d]: effacer, [m]: modifier, [q]: quitter";
-        println!( "le Ca sélectionné est : {}, que voulez vous faire ? ", c);
-        println!( "{choices}" );
+        println!( "{}", msg!( "menu.selected", c ) );
+        Dialog::display_entries( c );
+        println!( "{}", msg!( "menu.choices" ) );
+        Dialog::read_choice()
+    }
+
+    /// menu_month : affiche un mois (cf. fonction show_month) et propose de choisir un de ses
+    /// jours ou de passer à un autre mois
+    /// - params : m -> le mois à afficher
+    /// - return : une String contenant la valeur saisie par l'utilisateur
+    pub fn menu_month( m: CaMonth ) -> String {
+        Dialog::show_month( m );
+        println!( "{}", msg!( "month.choices" ) );
+        Dialog::read_choice()
+    }
+
+    /// read_choice : lit le choix de l'utilisateur dans un menu
+    /// - params : aucun
+    /// - return : une String contenant la valeur saisie, q (quitter) à la fin de l'entrée standard
+    fn read_choice() -> String {
         let mut input = String::new();
-        std::io::stdin().read_line( &mut input ).unwrap();
-        return input.trim().to_string();
+        match std::io::stdin().read_line( &mut input ) {
+            Ok(0) | Err(_) => "q".to_string(),
+            Ok(_) => input.trim().to_string(),
+        }
     }
 
-    /// dialog_Ca : propose d'enregistrer les différents parametres d'un chiffre d'affaire
-    /// - params : ca -> le Ca à modifier
-    /// - return : le Ca modifié
-    pub fn dialog_ca( mut c: Ca ) -> Ca {
-        println!( "le Ca concerné est : {c}" );
+    /// display_entries : affichage numéroté des activités d'un Ca lorsqu'il en a plusieurs.
+    /// - params : ca -> une référence sur le Ca concerné
+    /// - return : aucun
+    fn display_entries( c: &Ca ) {
+        if c.entries.len() < 2 { return; }
+        for (i, e) in c.entries.iter().enumerate() {
+            println!( "\t{}. {e}", i + 1 );
+        }
+    }
+
+    /// ask : affiche une question et renvoi la réponse saisie par l'utilisateur
+    /// - params : question -> le texte de la question
+    /// - return : une String contenant la réponse (sans les espaces de début et de fin), vide à
+    ///   la fin de l'entrée standard, sinon l'erreur Io
+    fn ask( question: &str ) -> Result<String> {
+        print!( "\t{question} : " );
+        io::stdout().flush()?;
         let mut input = String::new();
+        std::io::stdin().read_line( &mut input )?;
+        Ok( input.trim().to_string() )
+    }
+
+    /// choose_entry : demande le numéro de l'activité concernée lorsque le Ca en a plusieurs
+    /// - params : ca -> une référence sur le Ca concerné
+    /// - return : l'indice de l'activité choisie dans c.entries, sinon l'erreur de lecture
+    fn choose_entry( c: &Ca ) -> Result<usize> {
+        if c.entries.len() < 2 { return Ok(0); }
+        Dialog::display_entries( c );
+        let n: usize = Dialog::ask( &msg!( "ask.entry" ) )?.parse().unwrap_or( 1 );
+        Ok( n.clamp( 1, c.entries.len() ) - 1 )
+    }
 
-        print!( "\tchiffre d'affaire : " );
-        io::stdout().flush().unwrap();
-        std::io::stdin().read_line( &mut input ).unwrap();
-        c.ca = input.trim().parse().unwrap();
+    /// dialog_entry : propose d'enregistrer les différents parametres d'une activité. La saisie
+    /// est recommencée tant qu'elle n'est pas valide (cf. Entry::with_values)
+    /// - params :
+    ///     - e -> l'entrée à modifier
+    ///     - with_activity -> demande aussi le nom de l'activité
+    /// - return : l'entrée modifiée, sinon l'erreur de lecture
+    fn dialog_entry( mut e: Entry, with_activity: bool ) -> Result<Entry> {
+        if with_activity { e.activity = Dialog::ask( &msg!( "ask.activity" ) )?; }
+        loop {
+            let ca = Dialog::ask( &msg!( "ask.ca" ) )?;
+            let hours = Dialog::ask( &msg!( "ask.hours" ) )?;
+            let hsup = Dialog::ask( &msg!( "ask.hsup" ) )?;
+            let m = Dialog::ask( &msg!( "ask.comment" ) )?;
+            match e.clone().with_values( &ca, &hours, &hsup, &m ) {
+                Ok(valid) => return Ok(valid),
+                Err(error) => println!( "\t{}", msg!( "entry.retry", error ) ),
+            }
+        }
+    }
 
-        print!( "\tnb heures : " );
-        io::stdout().flush().unwrap();
-        input = "".to_string();
-        std::io::stdin().read_line( &mut input ).unwrap();
-        c.hours = input.trim().parse().unwrap();
+    /// dialog_Ca : propose d'enregistrer les différents parametres d'un chiffre d'affaire. Si
+    /// la journée contient plusieurs activités, l'activité à modifier est demandée.
+    /// - params : ca -> le Ca à modifier
+    /// - return : le Ca modifié, sinon l'erreur de lecture
+    pub fn dialog_ca( mut c: Ca ) -> Result<Ca> {
+        println!( "{}", msg!( "ca.current", c ) );
+        if c.entries.is_empty() { c.entries.push( Entry::default() ); }
 
-        print!( "\tdont hsup : " );
-        io::stdout().flush().unwrap();
-        input = "".to_string();
-        std::io::stdin().read_line( &mut input ).unwrap();
-        c.hsup = input.trim().parse().unwrap();
+        let i = Dialog::choose_entry( &c )?;
+        c.entries[i] = Dialog::dialog_entry( c.entries[i].clone(), c.entries.len() > 1 )?;
+        c.tags = tag::parse_list( &Dialog::ask( &msg!( "ask.tags" ) )? );
+        c.total();
 
-        print!( "\tcommentaire : " );
-        io::stdout().flush().unwrap();
-        input = "".to_string();
-        std::io::stdin().read_line( &mut input ).unwrap();
-        let m = input.trim().to_string();
-        if m.len() == 0 { c.comment = None; } else { c.comment = Some(m); }
+        println!( "\n{}", msg!( "ca.now", c ) );
+        Ok(c)
+    }
 
-        println!( "\nle Ca est maintenant : {c}" );
+    /// dialog_add_entry : ajoute une activité au Ca et propose d'enregistrer ses parametres
+    /// - params : ca -> le Ca à modifier
+    /// - return : le Ca modifié, sinon l'erreur de lecture
+    pub fn dialog_add_entry( mut c: Ca ) -> Result<Ca> {
+        println!( "{}", msg!( "entry.new", i18n::iso_date( &c.date ) ) );
+        c.entries.push( Dialog::dialog_entry( Entry::default(), true )? );
+        c.total();
 
-        c
+        println!( "\n{}", msg!( "ca.now", c ) );
+        Dialog::display_entries( &c );
+        Ok(c)
     }
 
+    /// dialog_remove_entry : retire une activité du Ca
+    /// - params : ca -> le Ca à modifier
+    /// - return : le Ca modifié, sinon l'erreur de lecture
+    pub fn dialog_remove_entry( mut c: Ca ) -> Result<Ca> {
+        if c.entries.len() < 2 {
+            println!( "{}", msg!( "entry.only_one" ) );
+            return Ok(c);
+        }
+        let i = Dialog::choose_entry( &c )?;
+        c.entries.remove( i );
+        c.total();
+
+        println!( "\n{}", msg!( "ca.now", c ) );
+        Ok(c)
+    }
 }
@@ -0,0 +1,150 @@
+//! # doctor :
+//! Ce module définit une structure Anomaly représentant une valeur incorrecte d'une entrée de la
+//! table ENTRY (saisie par l'application python ou à la main), ainsi que les règles qui
+//! permettent de les détecter et, quand c'est possible, de les réparer.
+//! - author : Thierry Probst <thierry.probst@free.fr>
+//! - version : 1.0.0
+//! - date : 19/10/2026
+use std::fmt;
+use chrono::NaiveDate;
+use rusqlite::types::Value;
+use crate::i18n::{self, msg};
+
+
+/// les formats de date reconnus pour réparer une date qui n'est pas au format ISO (yyyy-mm-dd).
+/// Le premier lit aussi les dates ISO sans zéros (2024-5-2) ou entourées d'espaces.
+const DATE_FORMATS: &[&str] = &[ "%Y-%m-%d", "%d/%m/%Y", "%Y/%m/%d", "%d-%m-%Y", "%d.%m.%Y", "%Y%m%d" ];
+
+/// la réparation d'une anomalie
+#[derive(Debug, Clone, PartialEq)]
+pub enum Repair {
+    /// le nombre d'heures NULL est remplacé par 0
+    ZeroHours,
+    /// la date est remplacée par la même date au format ISO
+    Date(String),
+    /// le chiffre d'affaire texte est remplacé par sa valeur numérique
+    Ca(f64),
+}
+
+#[derive(Debug)]
+pub struct Anomaly {
+    /// l'identifiant de l'entrée concernée dans la table ENTRY
+    pub entry_id: i64,
+    /// l'identifiant du profil de l'entrée
+    pub profile: i64,
+    /// la date de l'entrée, telle qu'elle est enregistrée
+    pub date: String,
+    /// la description de l'anomalie
+    pub description: String,
+    /// la réparation possible, None si l'anomalie doit être corrigée à la main
+    pub repair: Option<Repair>,
+}
+
+impl Anomaly {
+    /// check_entry : recherche les anomalies des valeurs brutes d'une entrée
+    /// - params :
+    ///     - entry_id, profile -> l'identifiant de l'entrée et celui de son profil
+    ///     - date, ca, hours, hsup -> les valeurs enregistrées, quel que soit leur type
+    ///     - today -> la date du jour, une entrée postérieure est une anomalie
+    /// - return : un Vecteur contenant les anomalies trouvées (vide si l'entrée est correcte)
+    pub fn check_entry( entry_id: i64, profile: i64, date: &Value, ca: &Value, hours: &Value, hsup: &Value,
+        today: NaiveDate ) -> Vec<Anomaly> {
+        let date_str = Anomaly::to_text( date );
+        let mut v = Vec::new();
+        let mut push = |description: String, repair: Option<Repair>|
+            v.push( Anomaly{ entry_id, profile, date: date_str.clone(), description, repair } );
+
+        match NaiveDate::parse_from_str( &date_str, "%Y-%m-%d" ) {
+            Ok(d) if d.format("%Y-%m-%d").to_string() == date_str => if d > today {
+                push( msg!( "doctor.future" ), None );
+            },
+            _ => push( msg!( "doctor.not_iso" ), Anomaly::parse_date( &date_str )
+                .map( |d| Repair::Date( d.format("%Y-%m-%d").to_string() ) ) ),
+        }
+        match ca {
+            Value::Text(s) => push( msg!( "doctor.ca_text", s ), Anomaly::parse_number( s )
+                .map( Repair::Ca ) ),
+            Value::Blob(_) => push( msg!( "doctor.ca_blob" ), None ),
+            _ => (),
+        }
+        match (hours, hsup) {
+            (Value::Null, _) => push( msg!( "doctor.hours_null" ), Some( Repair::ZeroHours ) ),
+            (h, hs) => if let (Some(h), Some(hs)) = (Anomaly::to_number( h ), Anomaly::to_number( hs )) {
+                if hs > h {
+                    push( msg!( "entry.hsup_above", i18n::decimal( hs ), i18n::decimal( h ) ), None );
+                }
+            },
+        }
+        v
+    }
+
+    /// to_text : la représentation texte d'une valeur sqlite
+    /// - params : v -> la valeur
+    /// - return : une String, "NULL" pour une valeur nulle
+    fn to_text( v: &Value ) -> String {
+        match v {
+            Value::Null => "NULL".to_string(),
+            Value::Integer(i) => i.to_string(),
+            Value::Real(r) => r.to_string(),
+            Value::Text(s) => s.clone(),
+            Value::Blob(b) => format!( "<{}>", msg!( "doctor.bytes", b.len() ) ),
+        }
+    }
+
+    /// to_number : la valeur numérique d'une valeur sqlite
+    /// - params : v -> la valeur
+    /// - return : le nombre, None si la valeur n'est pas numérique
+    fn to_number( v: &Value ) -> Option<f64> {
+        match v {
+            Value::Integer(i) => Some( *i as f64 ),
+            Value::Real(r) => Some( *r ),
+            _ => None,
+        }
+    }
+
+    /// parse_number : lit un nombre saisi sous forme de texte ("1 200,50 €" par exemple)
+    /// - params : s -> le texte
+    /// - return : le nombre, None si le texte n'en est pas un
+    fn parse_number( s: &str ) -> Option<f64> {
+        let s: String = s.chars().filter( |c| !c.is_whitespace() && *c != '€' ).collect();
+        s.replace( ',', "." ).parse().ok()
+    }
+
+    /// parse_date : lit une date dans l'un des formats de DATE_FORMATS
+    /// - params : s -> le texte
+    /// - return : la date, None si le texte n'est pas une date reconnue
+    fn parse_date( s: &str ) -> Option<NaiveDate> {
+        DATE_FORMATS.iter().find_map( |f| NaiveDate::parse_from_str( s.trim(), f ).ok() )
+    }
+}
+
+impl fmt::Display for Anomaly {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", msg!( "doctor.anomaly", self.date, self.entry_id, self.profile, self.description ))?;
+        match &self.repair {
+            Some(Repair::ZeroHours) => write!(f, " => {}", msg!( "doctor.repairable", msg!( "doctor.zero_hours" ) )),
+            Some(Repair::Date(d)) => write!(f, " => {}", msg!( "doctor.repairable", d )),
+            Some(Repair::Ca(ca)) => write!(f, " => {}", msg!( "doctor.repairable", i18n::decimal( ca ) )),
+            None => write!(f, " => {}", msg!( "doctor.by_hand" )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_non_iso_date_is_repaired() {
+        let today = NaiveDate::from_ymd_opt( 2026, 10, 19 ).unwrap();
+        for date in ["2024-5-2", " 2024-05-02", "02/05/2024", "2024/05/02", "02.05.2024", "20240502"] {
+            let v = Anomaly::check_entry( 1, 1, &Value::Text( date.to_string() ), &Value::Real( 1.0 ), &Value::Real( 1.0 ),
+                &Value::Real( 0.0 ), today );
+            assert_eq!( v.len(), 1, "{date}" );
+            assert_eq!( v[0].repair, Some( Repair::Date( "2024-05-02".to_string() ) ), "{date}" );
+        }
+        let v = Anomaly::check_entry( 1, 1, &Value::Text( "2024-05-02".to_string() ), &Value::Real( 1.0 ), &Value::Real( 1.0 ),
+            &Value::Real( 0.0 ), today );
+        assert!( v.is_empty() );
+    }
+}
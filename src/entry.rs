@@ -0,0 +1,77 @@
+//! # entry :
+//! Ce module définit une structure Entry représentant une activité réalisée au cours d'une
+//! journée. Une journée (Ca) peut contenir plusieurs entrées, elle en est alors le total.
+//! Cela correspond à un enregistrement de la table ENTRY du fichier Sqlite3
+//! - author : Thierry Probst <thierry.probst@free.fr>
+//! - version : 1.0.0
+//! - date : 19/10/2026
+use std::fmt;
+use crate::i18n::{self, msg};
+
+
+#[derive(Debug, Clone, Default)]
+pub struct Entry {
+    /// l'identifiant de l'entrée dans la table ENTRY (None tant qu'elle n'est pas enregistrée)
+    pub id: Option<i64>,
+    /// l'activité concernée (lieu, type de travail, ...)
+    pub activity: String,
+    /// le chiffre d'affaire réalisé
+    pub ca: f32,
+    /// le nombre d'heure travaillé
+    pub hours: f32,
+    /// le nombre d'heure supplémentaires réalisées
+    pub hsup: f32,
+    /// un éventuel commentaire lié à l'activité
+    pub comment: Option<String>,
+}
+
+impl Entry {
+    /// with_values : renseigne les valeurs d'une activité à partir de saisies texte (terminal ou
+    /// formulaire web) et les valide (cf. fonction validate)
+    /// - params :
+    ///     - self -> l'activité à renseigner
+    ///     - ca, hours, hsup -> les nombres saisis (la virgule est acceptée, vide vaut 0)
+    ///     - comment -> le commentaire saisi (vide pour aucun)
+    /// - return : l'activité renseignée, ou un message expliquant l'erreur de saisie
+    pub fn with_values( mut self, ca: &str, hours: &str, hsup: &str, comment: &str ) -> Result<Entry, String> {
+        self.ca = Entry::parse_number( &msg!( "entry.ca" ), ca )?;
+        self.hours = Entry::parse_number( &msg!( "entry.hours" ), hours )?;
+        self.hsup = Entry::parse_number( &msg!( "entry.hsup" ), hsup )?;
+        let comment = comment.trim();
+        self.comment = if comment.is_empty() { None } else { Some( comment.to_string() ) };
+        self.validate()?;
+        Ok(self)
+    }
+
+    /// validate : vérifie la cohérence des valeurs d'une activité : les nombres sont positifs et
+    /// les heures supplémentaires ne dépassent pas les heures
+    /// - params : self -> l'activité à vérifier
+    /// - return : un message expliquant l'erreur si l'activité n'est pas valide
+    pub fn validate( &self ) -> Result<(), String> {
+        if self.ca < 0.0 || self.hours < 0.0 || self.hsup < 0.0 {
+            return Err( msg!( "entry.negative" ) );
+        }
+        if self.hsup > self.hours {
+            return Err( msg!( "entry.hsup_above", i18n::decimal( self.hsup ), i18n::decimal( self.hours ) ) );
+        }
+        Ok(())
+    }
+
+    /// parse_number : lit un nombre saisi
+    /// - params :
+    ///     - name -> le nom de la valeur, pour le message d'erreur
+    ///     - s -> la saisie (la virgule est acceptée, vide vaut 0)
+    /// - return : le nombre, ou un message expliquant l'erreur de saisie
+    fn parse_number( name: &str, s: &str ) -> Result<f32, String> {
+        let s = s.trim().replace( ',', "." );
+        if s.is_empty() { return Ok(0.0); }
+        s.parse::<f32>().ok().filter( |n| n.is_finite() ).ok_or( msg!( "entry.not_a_number", name, s ) )
+    }
+}
+
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} : {} [{}/{} '{:?}']", self.activity, i18n::decimal( self.ca ), i18n::decimal( self.hours ),
+            i18n::decimal( self.hsup ), self.comment)
+    }
+}
@@ -0,0 +1,91 @@
+//! # error :
+//! Ce module définit l'erreur de fde. Chaque sorte d'erreur a son propre code de sortie du
+//! programme (cf. Error::exit_code) :
+//! - 2 -> un argument de la ligne de commande invalide (Parse)
+//! - 3 -> une saisie refusée (Validation)
+//! - 4 -> une configuration incomplète, un profil inconnu par exemple (Config)
+//! - 5 -> une erreur du fichier sqlite3 (Db)
+//! - 6 -> une erreur d'entrée / sortie, un fichier qui ne peut pas être écrit par exemple (Io)
+//! - author : Thierry Probst <thierry.probst@free.fr>
+//! - version : 1.0.0
+//! - date : 19/10/2026
+use std::fmt;
+use std::io;
+use crate::i18n::msg;
+
+
+/// le Result des fonctions de fde qui peuvent échouer de plusieurs façons
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// les erreurs de fde
+#[derive(Debug)]
+pub enum Error {
+    /// une erreur du fichier sqlite3
+    Db(rusqlite::Error),
+    /// une saisie refusée, avec le message qui explique pourquoi
+    Validation(String),
+    /// un argument de la ligne de commande invalide, avec le message à afficher (l'usage de la
+    /// commande en général)
+    Parse(String),
+    /// une erreur d'entrée / sortie
+    Io(io::Error),
+    /// une configuration incomplète ou incohérente, avec le message à afficher
+    Config(String),
+}
+
+impl Error {
+    /// exit_code : le code de sortie du programme correspondant à l'erreur (cf. en-tête du module)
+    /// - params : &self -> une référence sur l'erreur
+    /// - return : un code différent pour chaque sorte d'erreur
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Error::Parse(_) => 2,
+            Error::Validation(_) => 3,
+            Error::Config(_) => 4,
+            Error::Db(_) => 5,
+            Error::Io(_) => 6,
+        }
+    }
+
+    /// file : une erreur d'écriture de fichier, dont le message contient le nom du fichier
+    /// - params :
+    ///     - file -> le nom du fichier
+    ///     - e -> l'erreur d'entrée / sortie
+    /// - return : une erreur Io
+    pub fn file(file: &str, e: io::Error) -> Error {
+        Error::Io( io::Error::new( e.kind(), msg!( "file.not_written", file, e ) ) )
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Db(e) => write!( f, "{}", msg!( "error.db", e ) ),
+            Error::Validation(s) => write!( f, "{}", msg!( "error.validation", s ) ),
+            Error::Io(e) => write!( f, "{}", msg!( "error.io", e ) ),
+            Error::Parse(s) | Error::Config(s) => write!( f, "{s}" ),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Db(e) => Some( e ),
+            Error::Io(e) => Some( e ),
+            _ => None,
+        }
+    }
+}
+
+impl From<rusqlite::Error> for Error {
+    fn from(e: rusqlite::Error) -> Error {
+        Error::Db( e )
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Error {
+        Error::Io( e )
+    }
+}
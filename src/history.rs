@@ -0,0 +1,52 @@
+//! # history :
+//! Ce module définit une structure History représentant une modification d'une entrée (ajout,
+//! modification ou suppression). Cela correspond à un enregistrement de la table HISTORY du
+//! fichier Sqlite3, alimentée par des triggers sur la table ENTRY : les modifications faites par
+//! l'application python sont donc aussi tracées.
+//! - author : Thierry Probst <thierry.probst@free.fr>
+//! - version : 1.0.0
+//! - date : 19/10/2026
+use std::fmt;
+use crate::entry::Entry;
+use crate::i18n;
+
+
+/// la source enregistrée pour les modifications faites par la ligne de commande
+pub const SOURCE_CLI: &str = "cli";
+/// la source enregistrée pour les modifications faites par l'API REST (cf. fichier server.rs)
+pub const SOURCE_API: &str = "api";
+/// la source enregistrée pour les modifications faites par les pages web (cf. fichier web.rs)
+pub const SOURCE_WEB: &str = "web";
+
+#[derive(Debug)]
+pub struct History {
+    /// l'identifiant de la modification dans la table HISTORY
+    pub id: i64,
+    /// l'identifiant de l'entrée concernée dans la table ENTRY
+    pub entry_id: i64,
+    /// la date et l'heure de la modification
+    pub ts: String,
+    /// le type de modification : add, update, delete, trash (mise à la corbeille) ou untrash
+    pub op: String,
+    /// l'origine de la modification : cli, api, web, ou externe pour une modification faite
+    /// directement dans le fichier sqlite3 (application python)
+    pub source: String,
+    /// la date de la journée concernée
+    pub date: String,
+    /// l'entrée avant la modification (None pour un ajout)
+    pub old: Option<Entry>,
+    /// l'entrée après la modification (None pour une suppression)
+    pub new: Option<Entry>,
+}
+
+impl fmt::Display for History {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:>4}. {} {:<7} {:<7}", self.id, i18n::timestamp( &self.ts ), self.op, self.source)?;
+        match (&self.old, &self.new) {
+            (Some(o), Some(n)) => write!(f, " ({o}) => ({n})"),
+            (Some(o), None) => write!(f, " ({o})"),
+            (None, Some(n)) => write!(f, " ({n})"),
+            (None, None) => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,779 @@
+//! # i18n :
+//! Ce module contient le catalogue des messages affichés par fde, en français (fr) et en anglais
+//! (en), ainsi que le format des nombres et des dates de chaque langue : virgule décimale et
+//! dd/mm/yyyy en français, point décimal et yyyy-mm-dd en anglais. La langue est choisie par
+//! l'option --lang=fr|en, sinon par la variable d'environnement LANG (le français par défaut).
+//! - author : Thierry Probst <thierry.probst@free.fr>
+//! - version : 1.0.0
+//! - date : 19/10/2026
+use std::env;
+use std::fmt::Display;
+use std::sync::OnceLock;
+use chrono::{NaiveDate, NaiveDateTime};
+
+
+/// les langues de l'interface
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Lang {
+    Fr,
+    En,
+}
+
+/// la langue choisie au démarrage (cf. fonction init)
+static LANG: OnceLock<Lang> = OnceLock::new();
+
+impl Lang {
+    /// select : choisit la langue à partir de l'option --lang=fr|en, sinon de la variable
+    /// d'environnement LANG (fr_FR.UTF-8, en_GB.UTF-8, ...). Une variable LANG absente, C ou
+    /// POSIX laisse l'interface en français, toute autre langue que le français donne l'anglais.
+    /// - params : args -> les arguments de la ligne de commande
+    /// - return : la langue, None si l'option --lang indique une langue inconnue
+    pub fn select( args: &[String] ) -> Option<Lang> {
+        if let Some(code) = args.iter().find_map( |a| a.strip_prefix("--lang=") ) {
+            return match code.to_lowercase().as_str() {
+                "fr" => Some( Lang::Fr ),
+                "en" => Some( Lang::En ),
+                _ => None,
+            };
+        }
+        let lang = env::var( "LANG" ).unwrap_or_default().to_lowercase();
+        match lang.as_str() {
+            "" | "c" | "posix" => Some( Lang::Fr ),
+            l if l.starts_with( "c." ) || l.starts_with( "fr" ) => Some( Lang::Fr ),
+            _ => Some( Lang::En ),
+        }
+    }
+
+    /// code : le code ISO 639-1 de la langue
+    /// - params : self -> la langue
+    /// - return : fr ou en
+    pub fn code( &self ) -> &'static str {
+        match self {
+            Lang::Fr => "fr",
+            Lang::En => "en",
+        }
+    }
+}
+
+/// init : fixe la langue de l'interface, une seule fois au démarrage
+/// - params : lang -> la langue
+/// - return : aucun
+pub fn init( lang: Lang ) {
+    let _ = LANG.set( lang );
+}
+
+/// lang : la langue de l'interface
+/// - params : aucun
+/// - return : la langue choisie par init, le français sinon
+pub fn lang() -> Lang {
+    LANG.get().copied().unwrap_or( Lang::Fr )
+}
+
+/// tr : un message du catalogue dans la langue de l'interface
+/// - params : key -> la clé du message
+/// - return : le message, la clé elle même si elle est absente du catalogue
+pub fn tr( key: &'static str ) -> &'static str {
+    match MESSAGES.iter().find( |m| m.0 == key ) {
+        Some((_, fr, en)) => if lang() == Lang::Fr { fr } else { en },
+        None => key,
+    }
+}
+
+/// fill : remplace les {0}, {1}, ... d'un message par les arguments, en un seul passage de
+/// gauche à droite sur le message : le texte d'un argument (un commentaire contenant {1} par
+/// exemple) n'est jamais remplacé à son tour
+/// - params :
+///     - message -> le message (cf. fonction tr)
+///     - args -> les arguments, dans l'ordre de leur numéro
+/// - return : le message complété (un numéro sans argument est laissé tel quel)
+pub fn fill( message: &str, args: &[&dyn Display] ) -> String {
+    let mut s = String::with_capacity( message.len() );
+    let mut rest = message;
+    while let Some(open) = rest.find( '{' ) {
+        s.push_str( &rest[..open] );
+        rest = &rest[open..];
+        // {n} : l'argument n et la position de l'accolade fermante
+        let arg = rest.find( '}' ).and_then( |close| Some(( close, args.get( rest[1..close].parse::<usize>().ok()? )? )) );
+        match arg {
+            Some((close, a)) => { s.push_str( &a.to_string() ); rest = &rest[close + 1..]; },
+            None => { s.push( '{' ); rest = &rest[1..]; },
+        }
+    }
+    s.push_str( rest );
+    s
+}
+
+/// msg : un message du catalogue dans la langue de l'interface, complété par les arguments
+/// (cf. fonctions tr et fill)
+#[macro_export]
+macro_rules! msg {
+    ($key:expr) => { $crate::i18n::tr( $key ).to_string() };
+    ($key:expr, $($arg:expr),+ $(,)?) => {
+        $crate::i18n::fill( $crate::i18n::tr( $key ), &[ $( &$arg as &dyn std::fmt::Display ),+ ] )
+    };
+}
+pub use crate::msg;
+
+/// decimal : un nombre au format de la langue, avec toutes ses décimales
+/// - params : v -> le nombre
+/// - return : le nombre formaté (virgule décimale en français)
+pub fn decimal<T: Display>( v: T ) -> String {
+    localize( v.to_string() )
+}
+
+/// number : un nombre au format de la langue, avec un nombre fixe de décimales
+/// - params : v, decimals -> le nombre et le nombre de décimales
+/// - return : le nombre formaté (virgule décimale en français)
+pub fn number( v: f64, decimals: usize ) -> String {
+    localize( format!( "{v:.decimals$}" ) )
+}
+
+/// signed : un nombre au format de la langue, toujours précédé de son signe
+/// - params : v, decimals -> le nombre et le nombre de décimales
+/// - return : le nombre formaté
+pub fn signed( v: f64, decimals: usize ) -> String {
+    localize( format!( "{v:+.decimals$}" ) )
+}
+
+/// localize : remplace le point décimal par une virgule en français
+/// - params : s -> un nombre formaté par Rust
+/// - return : le nombre au format de la langue
+fn localize( s: String ) -> String {
+    match lang() {
+        Lang::Fr => s.replace( '.', "," ),
+        Lang::En => s,
+    }
+}
+
+/// date : une date au format de la langue
+/// - params : d -> la date
+/// - return : dd/mm/yyyy en français, yyyy-mm-dd en anglais
+pub fn date( d: &NaiveDate ) -> String {
+    match lang() {
+        Lang::Fr => d.format("%d/%m/%Y").to_string(),
+        Lang::En => d.format("%Y-%m-%d").to_string(),
+    }
+}
+
+/// month : un mois au format de la langue
+/// - params : d -> un jour du mois
+/// - return : mm/yyyy en français, yyyy-mm en anglais
+pub fn month( d: &NaiveDate ) -> String {
+    match lang() {
+        Lang::Fr => d.format("%m/%Y").to_string(),
+        Lang::En => d.format("%Y-%m").to_string(),
+    }
+}
+
+/// day_month : un jour sans son année, au format de la langue (étiquettes des graphiques)
+/// - params : d -> la date
+/// - return : dd/mm en français, mm-dd en anglais
+pub fn day_month( d: &NaiveDate ) -> String {
+    match lang() {
+        Lang::Fr => d.format("%d/%m").to_string(),
+        Lang::En => d.format("%m-%d").to_string(),
+    }
+}
+
+/// short_month : un mois avec l'année sur deux chiffres, au format de la langue (étiquettes des
+/// graphiques)
+/// - params : d -> un jour du mois
+/// - return : mm/yy en français, yy-mm en anglais
+pub fn short_month( d: &NaiveDate ) -> String {
+    match lang() {
+        Lang::Fr => d.format("%m/%y").to_string(),
+        Lang::En => d.format("%y-%m").to_string(),
+    }
+}
+
+/// iso_date : une date enregistrée au format yyyy-mm-dd, au format de la langue
+/// - params : s -> la date enregistrée
+/// - return : la date formatée, ou le texte tel quel si ce n'est pas une date valide
+pub fn iso_date( s: &str ) -> String {
+    NaiveDate::parse_from_str( s, "%Y-%m-%d" ).map( |d| date( &d ) ).unwrap_or( s.to_string() )
+}
+
+/// timestamp : un horodatage enregistré au format yyyy-mm-dd hh:mm:ss, au format de la langue
+/// - params : s -> l'horodatage enregistré
+/// - return : l'horodatage formaté, ou le texte tel quel s'il n'est pas valide
+pub fn timestamp( s: &str ) -> String {
+    NaiveDateTime::parse_from_str( s, "%Y-%m-%d %H:%M:%S" )
+        .map( |t| format!( "{} {}", date( &t.date() ), t.format("%H:%M:%S") ) ).unwrap_or( s.to_string() )
+}
+
+/// le catalogue des messages : (clé, français, anglais)
+const MESSAGES: &[(&str, &str, &str)] = &[
+    ("welcome",
+        "
+        fde est un programe permettant de suivre l'activité de Nat (et des autres profils).
+        {0} v{1} du {2}
+        ",
+        "
+        fde is a program to follow the activity of Nat (and of the other profiles).
+        {0} v{1} of {2}
+        "),
+    ("help",
+        "
+        Usage : ./fde [option]
+                ./fde compare <période A> <période B>
+                ./fde search <termes>
+                ./fde profile [nom [--threshold=seuil] [--rate=taux] [--wage=taux horaire] [--overtime=tranches]]
+                ./fde history <date>
+                ./fde restore <date> <n°>
+                ./fde backup [restore <fichier>]
+                ./fde doctor [--repair]
+                ./fde serve [--addr=ip:port] [--token=jeton]
+                ./fde export --format ics [--period=période] [--output=fichier]
+                ./fde statement --month mm/yyyy [--output=fichier] [--pdf]
+                ./fde trash [restore <date> | days <n> | purge]
+                ./fde chart [month mm/yyyy | year yyyy | weeks <période>]
+                ./fde chart --svg fichier.svg [période]
+                ./fde pay [--month mm/yyyy]
+                ./fde payslip [--month mm/yyyy [--gross=brut] [--net=net] [--prime=prime] [--overtime=hsup]]
+                ./fde reconcile [période]
+                ./fde summary [mm/yyyy]
+        Commandes :
+        compare         : compare les valeurs de deux périodes (CA, heures, hsup, CA/heure,
+                          jours travaillés et prime). La période A sert de référence.
+                          une période s'écrit mm/yyyy (mois), Tn/yyyy (trimestre) ou yyyy (année).
+
+        search          : recherche les jours dont le commentaire contient tous les termes
+                          (les accents sont ignorés, un terme terminé par * est un préfixe).
+
+        profile         : sans argument, affiche la liste des profils (personnes suivies). avec
+                          un nom, crée ou modifie ce profil : seuil mensuel et taux (en %) de la
+                          prime, taux horaire brut (--wage) et tranches de majoration des heures
+                          supplémentaires de la semaine (--overtime=8:25,50 : 8 h à +25 %, puis
+                          +50 %).
+
+        history         : affiche l'historique des modifications d'un jour (ajout, modification
+                          ou suppression d'une activité), y compris celles de l'application python.
+
+        restore         : annule la modification n° de l'historique d'un jour, l'activité
+                          retrouve les valeurs qu'elle avait avant cette modification.
+
+        backup          : sauvegarde la base dans le répertoire backup (les 10 dernières sauvegardes
+                          sont conservées). la base est aussi sauvegardée avant chaque mise à jour
+                          de son schéma.
+
+        doctor          : vérifie l'intégrité de la base et recherche les anomalies des activités
+                          (heures NULL, hsup supérieures aux heures, date future ou pas au format
+                          yyyy-mm-dd, chiffre d'affaire texte). --repair répare celles qui peuvent
+                          l'être.
+
+        serve           : démarre l'API REST (JSON) sur 127.0.0.1:8037 par défaut : /days/yyyy-mm-dd
+                          (GET, PUT, DELETE), /months/yyyy-mm, /years/yyyy et
+                          /reports?period=... (GET). avec --token (ou FDE_TOKEN), les requêtes
+                          doivent contenir l'entête 'Authorization: Bearer jeton', obligatoire
+                          hors de la machine locale. les pages web (calendrier du mois, saisie
+                          d'un jour) sont sur http://127.0.0.1:8037/ ; avec un jeton, ouvrir une
+                          première fois l'url avec ?token=jeton.
+
+        export          : exporte les jours dans un calendrier iCalendar (fde.ics par défaut) : un
+                          évènement par jour (catégorie Travail, ou Congé pour les jours portant
+                          un tag congé, maladie, rtt, ...) et les jours fériés (catégorie Férié).
+
+        statement       : écrit le relevé du mois pour l'employeur (jours, totaux et calcul de la
+                          prime) au format HTML, ou PDF avec --pdf si fde a été compilé avec
+                          cargo build --features pdf.
+
+        backup restore  : remplace la base par une sauvegarde (fichier), après avoir vérifié son
+                          schéma. la base remplacée est elle-même sauvegardée.
+
+        trash           : affiche les jours effacés (placés dans la corbeille). restore <date>
+                          restaure un jour effacé, days <n> change la durée (en jours) de
+                          conservation des jours effacés, purge supprime définitivement ceux
+                          qui l'ont dépassée (tous profils confondus).
+
+        chart           : affiche un graphique en barres : le CA de chaque jour d'un mois (month),
+                          le CA de chaque mois d'une année comparé au seuil de la prime (year, par
+                          défaut) ou les heures de chaque semaine d'une période (weeks). avec
+                          --svg, écrit les graphiques du bilan de la période (l'année en cours par
+                          défaut) dans un fichier SVG : CA par mois et seuil de la prime, CA par
+                          heure et cumul des heures supplémentaires.
+
+        pay             : estime le salaire brut du mois (le mois en cours par défaut) pour le
+                          comparer au bulletin de paie : heures normales au taux horaire, heures
+                          supplémentaires majorées par tranches (semaine par semaine) et prime.
+
+        payslip         : sans argument, affiche les bulletins de paie enregistrés. avec --month,
+                          enregistre les montants payés ce mois (brut, net, prime et heures
+                          supplémentaires) relevés sur le bulletin.
+
+        reconcile       : rapproche les bulletins de paie de la période (l'année en cours par
+                          défaut) des montants calculés, et signale les mois où la prime ou les
+                          heures supplémentaires payées sont différentes.
+
+        summary         : affiche les totaux et les moyennes mensuelles (CA, heures, hsup) des 12
+                          mois glissants se terminant par le mois indiqué (le mois en cours par
+                          défaut) et de l'année de ce mois, avec le nombre de mois au dessus du
+                          seuil de la prime. les moyennes portent sur les mois saisis.
+
+        Options :
+        -h
+        --help          : cette aide.
+
+        -m=date
+        --month=date    : spécifie le mois qui sera affiché : un mois (mm/yyyy) ou une date
+                          de ce mois. un numéro de jour ouvre ce jour, [n] et [p] passent au
+                          mois suivant ou précédent.
+
+        -d=date
+        --day=date    : spécifie le jour qui sera affiché en vue de sa création, modification
+                        ou suppression. [n] et [p] passent au jour suivant ou précédent, [M]
+                        ouvre son mois. la session continue jusqu'à [q].
+
+        --profile=nom   : choisit la personne concernée par la commande (le premier profil,
+                          qui contient les données de l'application python, par défaut).
+
+        --lang=fr|en    : choisit la langue de l'interface (sinon d'après la variable LANG).
+
+        --tag=tag       : avec -m ou compare, limite les valeurs aux jours portant ce tag (peut
+                          être répété). les totaux par tag sont affichés à la fin du rapport.
+
+        Dates :
+        une date s'écrit dd/mm/yyyy, dd/mm (l'année en cours), yyyy-mm-dd, today ou aujourd'hui,
+        yesterday ou hier, -n (il y a n jours) ou le nom d'un jour (lundi : le dernier lundi,
+        aujourd'hui compris). une période s'écrit mm/yyyy, Tn/yyyy, yyyy ou comme une date (le
+        mois de cette date).
+
+        Codes de sortie :
+        0 : succès, 2 : argument invalide, 3 : saisie refusée, 4 : configuration (profil inconnu,
+        taux horaire absent, ...), 5 : erreur de la base de données, 6 : erreur d'entrée / sortie.
+        ",
+        "
+        Usage : ./fde [option]
+                ./fde compare <period A> <period B>
+                ./fde search <terms>
+                ./fde profile [name [--threshold=threshold] [--rate=rate] [--wage=hourly wage] [--overtime=brackets]]
+                ./fde history <date>
+                ./fde restore <date> <n°>
+                ./fde backup [restore <file>]
+                ./fde doctor [--repair]
+                ./fde serve [--addr=ip:port] [--token=token]
+                ./fde export --format ics [--period=period] [--output=file]
+                ./fde statement --month mm/yyyy [--output=file] [--pdf]
+                ./fde trash [restore <date> | days <n> | purge]
+                ./fde chart [month mm/yyyy | year yyyy | weeks <period>]
+                ./fde chart --svg file.svg [period]
+                ./fde pay [--month mm/yyyy]
+                ./fde payslip [--month mm/yyyy [--gross=gross] [--net=net] [--prime=bonus] [--overtime=overtime]]
+                ./fde reconcile [period]
+                ./fde summary [mm/yyyy]
+        Commands :
+        compare         : compares the values of two periods (revenue, hours, overtime, revenue per
+                          hour, worked days and bonus). Period A is the reference. A period is
+                          written mm/yyyy (month), Tn/yyyy (quarter) or yyyy (year).
+
+        search          : finds the days whose comment contains all the terms (accents are
+                          ignored, a term ending with * is a prefix).
+
+        profile         : without argument, lists the profiles (people followed). With a name,
+                          creates or updates this profile : monthly threshold and rate (in %) of
+                          the bonus, gross hourly wage (--wage) and weekly overtime brackets
+                          (--overtime=8:25,50 : 8 h at +25 %, then +50 %).
+
+        history         : shows the change history of a day (activity added, modified or
+                          deleted), including the changes made by the python application.
+
+        restore         : undoes change n° of the history of a day, the activity gets back the
+                          values it had before this change.
+
+        backup          : saves the database in the backup directory (the last 10 backups are
+                          kept). The database is also saved before each update of its schema.
+
+        doctor          : checks the integrity of the database and looks for activity anomalies
+                          (NULL hours, overtime above the hours, future date or date not in the
+                          yyyy-mm-dd format, text revenue). --repair fixes the repairable ones.
+
+        serve           : starts the REST API (JSON) on 127.0.0.1:8037 by default : /days/yyyy-mm-dd
+                          (GET, PUT, DELETE), /months/yyyy-mm, /years/yyyy and
+                          /reports?period=... (GET). With --token (or FDE_TOKEN), requests must
+                          carry the header 'Authorization: Bearer token', mandatory outside the
+                          local machine. The web pages (month calendar, day form) are on
+                          http://127.0.0.1:8037/ ; with a token, open the url once with
+                          ?token=token.
+
+        export          : exports the days to an iCalendar file (fde.ics by default) : one event
+                          per day (category Work, or Leave for the days tagged congé, maladie,
+                          rtt, ...) and the public holidays (category Public holiday).
+
+        statement       : writes the monthly statement for the employer (days, totals and bonus
+                          calculation) in HTML, or in PDF with --pdf if fde was built with
+                          cargo build --features pdf.
+
+        backup restore  : replaces the database with a backup (file), after checking its schema.
+                          The replaced database is itself saved.
+
+        trash           : shows the deleted days (moved to the trash). restore <date> restores a
+                          deleted day, days <n> changes how long (in days) deleted days are kept,
+                          purge permanently removes those kept longer (for all profiles).
+
+        chart           : shows a bar chart : the revenue of each day of a month (month), the
+                          revenue of each month of a year compared to the bonus threshold (year,
+                          by default) or the hours of each week of a period (weeks). With --svg,
+                          writes the charts of the period (the current year by default) to an SVG
+                          file : revenue per month and bonus threshold, revenue per hour and
+                          cumulated overtime.
+
+        pay             : estimates the gross pay of the month (the current month by default) to
+                          compare it with the payslip : normal hours at the hourly wage, overtime
+                          paid by brackets (week by week) and bonus.
+
+        payslip         : without argument, lists the recorded payslips. With --month, records
+                          the amounts paid this month (gross, net, bonus and overtime) read on
+                          the payslip.
+
+        reconcile       : reconciles the payslips of the period (the current year by default)
+                          with the computed amounts, and flags the months where the bonus or the
+                          overtime paid differ.
+
+        summary         : shows the totals and monthly averages (revenue, hours, overtime) of the
+                          rolling 12 months ending with the given month (the current month by
+                          default) and of the year of this month, with the number of months above
+                          the bonus threshold. The averages are over the months entered.
+
+        Options :
+        -h
+        --help          : this help.
+
+        -m=date
+        --month=date    : the month to show : a month (mm/yyyy) or a date of this month. A day
+                          number opens this day, [n] and [p] move to the next or previous month.
+
+        -d=date
+        --day=date    : the day to show in order to create, modify or delete it. [n] and [p]
+                        move to the next or previous day, [M] opens its month. The session
+                        goes on until [q].
+
+        --profile=name  : the person concerned by the command (the first profile, which holds
+                          the data of the python application, by default).
+
+        --lang=fr|en    : the interface language (otherwise taken from the LANG variable).
+
+        --tag=tag       : with -m or compare, limits the values to the days carrying this tag
+                          (may be repeated). The totals per tag are shown at the end of the report.
+
+        Dates :
+        a date is written dd/mm/yyyy, dd/mm (the current year), yyyy-mm-dd, today or aujourd'hui,
+        yesterday or hier, -n (n days ago) or the name of a day (monday : the last monday, today
+        included). A period is written mm/yyyy, Tn/yyyy, yyyy or as a date (the month of this
+        date).
+
+        Exit codes :
+        0 : success, 2 : invalid argument, 3 : rejected input, 4 : configuration (unknown profile,
+        missing hourly wage, ...), 5 : database error, 6 : input / output error.
+        "),
+    ("error.db", "erreur de la base de données : {0}", "database error : {0}"),
+    ("error.validation", "saisie invalide : {0}", "invalid input : {0}"),
+    ("error.io", "erreur d'entrée / sortie : {0}", "input / output error : {0}"),
+    ("error.title", "erreur", "error"),
+    ("command.unknown", "commande inconnue, cf. fde --help", "unknown command, see fde --help"),
+    ("option.unknown", "option inconnue", "unknown option"),
+    ("date.invalid", "date invalide, cf. fde --help", "invalid date, see fde --help"),
+    ("file.missing", "le fichier {0} n'existe pas", "the file {0} does not exist"),
+    ("file.not_written", "le fichier {0} n'a pas pu être écrit : {1}", "the file {0} could not be written : {1}"),
+    ("unit.days", "j", "d"),
+    ("filter", "--- Filtre : {0} ---", "--- Filter : {0} ---"),
+    ("tags.title", "--- Tags ---------------------------", "--- Tags ---------------------------"),
+    ("label.ca", "CA", "Revenue"),
+    ("label.hours", "Heures", "Hours"),
+    ("label.hsup", "HSup", "Overtime"),
+    ("label.delta", "Delta", "Delta"),
+    ("label.prime", "Prime", "Bonus"),
+    ("label.gap", "Ecart", "Gap"),
+    ("label.ca_hour", "CA/h", "Rev./h"),
+    ("label.days", "Jours", "Days"),
+    ("label.threshold", "seuil de la prime", "bonus threshold"),
+    ("month.title", "--- Valeurs pour le mois {0} ({1}) ---", "--- Values for month {0} ({1}) ---"),
+    ("month.datas", "--- Données ------------------------", "--- Data ---------------------------"),
+    ("month.no_data", "aucune donnée pour ce mois, choisissez un jour pour le saisir", "no data for this month, pick a day to enter it"),
+    ("compare.title", "--- Comparaison {0} / {1} ({2}) ---", "--- Comparison {0} / {1} ({2}) ---"),
+    ("search.title", "--- Recherche de '{0}' : {1} jour(s) ---", "--- Search for '{0}' : {1} day(s) ---"),
+    ("profiles.title", "--- Profils ------------------------", "--- Profiles -----------------------"),
+    ("history.title", "--- Historique du {0} ---", "--- History of {0} ---"),
+    ("trash.title",
+        "--- Corbeille : {0} jour(s), conservés {1} jours (cf. fde trash purge) ---",
+        "--- Trash : {0} day(s), kept {1} days (see fde trash purge) ---"),
+    ("trash.line", "{0} effacé le {1}", "{0} deleted on {1}"),
+    ("trash.purged",
+        "{0} activité(s) de la corbeille supprimée(s) définitivement",
+        "{0} activity(ies) permanently removed from the trash"),
+    ("trash.none", "aucun jour effacé le {0}", "no day deleted on {0}"),
+    ("trash.restored", "le {0} a été restauré ({1} activité(s))", "{0} has been restored ({1} activity(ies))"),
+    ("trash.days",
+        "les jours effacés sont conservés {0} jours (cf. fde trash purge)",
+        "deleted days are kept {0} days (see fde trash purge)"),
+    ("doctor.integrity", "--- Intégrité du fichier : {0} ---", "--- File integrity : {0} ---"),
+    ("doctor.anomalies", "--- Anomalies : {0} ({1} réparable(s)) ---", "--- Anomalies : {0} ({1} repairable) ---"),
+    ("doctor.anomaly", "{0} (entrée n°{1}, profil {2}) : {3}", "{0} (entry n°{1}, profile {2}) : {3}"),
+    ("doctor.future", "la date est dans le futur", "the date is in the future"),
+    ("doctor.not_iso",
+        "la date n'est pas au format ISO (yyyy-mm-dd)",
+        "the date is not in the ISO format (yyyy-mm-dd)"),
+    ("doctor.ca_text", "le chiffre d'affaire est un texte ('{0}')", "the revenue is a text ('{0}')"),
+    ("doctor.ca_blob", "le chiffre d'affaire est une donnée binaire", "the revenue is binary data"),
+    ("doctor.hours_null", "le nombre d'heures est NULL", "the number of hours is NULL"),
+    ("doctor.bytes", "{0} octets", "{0} bytes"),
+    ("doctor.repairable", "réparable ({0})", "repairable ({0})"),
+    ("doctor.zero_hours", "0 heure", "0 hours"),
+    ("doctor.by_hand", "à corriger à la main", "to be fixed by hand"),
+    ("doctor.repaired", "{0} anomalie(s) réparée(s)", "{0} anomaly(ies) repaired"),
+    ("doctor.use_repair",
+        "utilisez fde doctor --repair pour réparer les anomalies réparables",
+        "use fde doctor --repair to fix the repairable anomalies"),
+    ("entry.ca", "le chiffre d'affaire", "the revenue"),
+    ("entry.hours", "le nombre d'heures", "the number of hours"),
+    ("entry.hsup", "le nombre d'heures supplémentaires", "the number of overtime hours"),
+    ("entry.negative", "les valeurs doivent être positives", "the values must be positive"),
+    ("entry.hsup_above",
+        "les heures supplémentaires ({0}) dépassent les heures ({1})",
+        "the overtime ({0}) exceeds the hours ({1})"),
+    ("entry.not_a_number", "{0} doit être un nombre ('{1}')", "{0} must be a number ('{1}')"),
+    ("entry.retry", "{0}, recommencez la saisie", "{0}, please try again"),
+    ("entry.new", "nouvelle activité pour le {0} :", "new activity for {0} :"),
+    ("entry.only_one",
+        "le Ca ne contient qu'une activité, utilisez [d] pour l'effacer",
+        "the day has only one activity, use [d] to delete it"),
+    ("ca.created", "{0} a été créé dans la DB", "{0} has been created in the DB"),
+    ("ca.current", "le Ca concerné est : {0}", "the day concerned is : {0}"),
+    ("ca.now", "le Ca est maintenant : {0}", "the day is now : {0}"),
+    ("confirm.delete",
+        "effacer le {0} (il restera dans la corbeille, cf. fde trash) ? [o/n]",
+        "delete {0} (it will stay in the trash, see fde trash) ? [y/n]"),
+    ("menu.selected",
+        "le Ca sélectionné est : {0}, que voulez vous faire ? ",
+        "the selected day is : {0}, what do you want to do ? "),
+    ("menu.choices",
+        "[s]: sauvegarder, [d]: effacer, [m]: modifier, [a]: ajouter une activité, [r]: retirer une activité,
+[n]: jour suivant, [p]: jour précédent, [M]: mois, [q]: quitter",
+        "[s]: save, [d]: delete, [m]: modify, [a]: add an activity, [r]: remove an activity,
+[n]: next day, [p]: previous day, [M]: month, [q]: quit"),
+    ("month.choices",
+        "[1-31]: choisir un jour, [n]: mois suivant, [p]: mois précédent, [q]: quitter",
+        "[1-31]: pick a day, [n]: next month, [p]: previous month, [q]: quit"),
+    ("ask.entry", "n° de l'activité", "activity n°"),
+    ("ask.activity", "activité", "activity"),
+    ("ask.ca", "chiffre d'affaire", "revenue"),
+    ("ask.hours", "nb heures", "nb hours"),
+    ("ask.hsup", "dont hsup", "of which overtime"),
+    ("ask.comment", "commentaire", "comment"),
+    ("ask.tags", "tags (séparés par des virgules)", "tags (comma separated)"),
+    ("restore.done",
+        "la modification n°{0} ({1}) du {2} a été annulée",
+        "change n°{0} ({1}) of {2} has been undone"),
+    ("restore.other_day", "la modification {0} ne concerne pas le {1}", "change {0} does not concern {1}"),
+    ("backup.done",
+        "la base a été sauvegardée dans {0} ({1} sauvegardes conservées)",
+        "the database has been saved in {0} ({1} backups kept)"),
+    ("backup.restored",
+        "{0} a été restauré, l'ancienne base est sauvegardée dans {1}",
+        "{0} has been restored, the previous database is saved in {1}"),
+    ("backup.not_restored", "{0} n'a pas été restauré :", "{0} has not been restored :"),
+    ("schema.corrupted", "le fichier est corrompu : {0}", "the file is corrupted : {0}"),
+    ("schema.newer",
+        "le schéma (version {0}) est plus récent que celui de fde (version {1})",
+        "the schema (version {0}) is newer than the one of fde (version {1})"),
+    ("schema.missing",
+        "{0} est absent alors que le schéma est en version {1} (depuis la version {2})",
+        "{0} is missing although the schema is at version {1} (since version {2})"),
+    ("server.bad_address", "adresse invalide : {0}", "invalid address : {0}"),
+    ("server.token_required",
+        "un jeton (--token ou FDE_TOKEN) est obligatoire pour écouter sur {0}",
+        "a token (--token or FDE_TOKEN) is required to listen on {0}"),
+    ("server.listening", "fde écoute sur http://{0} (profil {1})", "fde is listening on http://{0} (profile {1})"),
+    ("server.respond", "la réponse n'a pas pu être envoyée : {0}", "the response could not be sent : {0}"),
+    ("server.failed", "le serveur n'a pas pu démarrer : {0}", "the server could not start : {0}"),
+    ("api.token", "jeton absent ou invalide", "missing or invalid token"),
+    ("api.too_large", "le contenu de la requête dépasse {0} Ko", "the request body exceeds {0} KB"),
+    ("api.origin", "requête envoyée depuis un autre site", "request sent from another site"),
+    ("api.unreadable", "le contenu de la requête n'est pas lisible", "the request body is not readable"),
+    ("api.method", "méthode non autorisée", "method not allowed"),
+    ("api.not_found", "donnée introuvable", "data not found"),
+    ("api.no_data", "aucune donnée le {0}", "no data on {0}"),
+    ("api.not_json", "le contenu n'est pas un objet JSON", "the body is not a JSON object"),
+    ("api.list", "{0} doit être une liste", "{0} must be a list"),
+    ("api.text_list", "{0} doit être une liste de textes", "{0} must be a list of texts"),
+    ("api.text", "{0} doit être un texte", "{0} must be a text"),
+    ("api.empty_day",
+        "un jour contient au moins une activité, utilisez DELETE pour l'effacer",
+        "a day holds at least one activity, use DELETE to remove it"),
+    ("api.month", "mois invalide (yyyy-mm)", "invalid month (yyyy-mm)"),
+    ("api.date", "date invalide (yyyy-mm-dd)", "invalid date (yyyy-mm-dd)"),
+    ("api.year", "année invalide (yyyy)", "invalid year (yyyy)"),
+    ("api.period",
+        "période absente ou invalide (?period=mm/yyyy, Tn/yyyy ou yyyy)",
+        "missing or invalid period (?period=mm/yyyy, Tn/yyyy or yyyy)"),
+    ("api.entry", "une activité doit être un objet JSON", "an activity must be a JSON object"),
+    ("api.positive", "{0} doit être un nombre positif", "{0} must be a positive number"),
+    ("api.profile", "profil inconnu : {0}", "unknown profile : {0}"),
+    ("api.resource", "ressource inconnue : {0}", "unknown resource : {0}"),
+    ("web.not_found", "page introuvable", "page not found"),
+    ("web.not_found_text", "cette page n'existe pas.", "this page does not exist."),
+    ("web.unauthorized", "accès refusé", "access denied"),
+    ("web.forbidden", "requête refusée", "request rejected"),
+    ("web.too_large", "requête trop longue", "request too large"),
+    ("web.forbidden_text",
+        "ce formulaire a été envoyé depuis un autre site : ouvrez la page du jour depuis fde pour l'enregistrer.",
+        "this form was sent from another site : open the page of the day from fde to save it."),
+    ("web.unauthorized_text",
+        "le jeton est absent ou invalide : ouvrez l'url une première fois avec <code>?token=jeton</code>, il est ensuite conservé par le navigateur.",
+        "the token is missing or invalid : open the url once with <code>?token=token</code>, the browser then keeps it."),
+    ("web.bad_month", "mois invalide", "invalid month"),
+    ("web.bad_month_text", "le mois doit être au format yyyy-mm.", "the month must be in the yyyy-mm format."),
+    ("web.bad_date", "date invalide", "invalid date"),
+    ("web.bad_date_text", "la date doit être au format yyyy-mm-dd.", "the date must be in the yyyy-mm-dd format."),
+    ("web.previous", "mois précédent", "previous month"),
+    ("web.next", "mois suivant", "next month"),
+    ("weekdays", "lun,mar,mer,jeu,ven,sam,dim", "Mon,Tue,Wed,Thu,Fri,Sat,Sun"),
+    ("web.summary", "Récapitulatif", "Summary"),
+    ("web.hsup", "dont hsup", "of which overtime"),
+    ("web.worked_days", "jours travaillés", "worked days"),
+    ("web.back", "retour au mois", "back to the month"),
+    ("web.columns",
+        "activité,chiffre d'affaire,heures,dont hsup,commentaire,retirer",
+        "activity,revenue,hours,of which overtime,comment,remove"),
+    ("web.tags", "tags (séparés par des virgules)", "tags (comma separated)"),
+    ("web.save", "enregistrer", "save"),
+    ("period.quarter", "T{0}/{1}", "Q{0}/{1}"),
+    ("profile.unknown",
+        "le profil {0} n'existe pas, cf. fde profile",
+        "the profile {0} does not exist, see fde profile"),
+    ("profile.saved", "le profil {0} a été enregistré", "the profile {0} has been saved"),
+    ("profile.line",
+        "{0} (seuil {1}, prime {2}%, taux horaire {3}, hsup {4})",
+        "{0} (threshold {1}, bonus {2}%, hourly wage {3}, overtime {4})"),
+    ("profile.bracket", "{0} h à {1}", "{0} h at {1}"),
+    ("profile.then", " puis ", " then "),
+    ("export.done",
+        "{0} jour(s) et {1} jour(s) férié(s) exportés dans {2}",
+        "{0} day(s) and {1} public holiday(s) exported to {2}"),
+    ("statement.done", "le relevé de {0} a été écrit dans {1}", "the statement of {0} has been written to {1}"),
+    ("statement.failed", "le relevé n'a pas pu être écrit : {0}", "the statement could not be written : {0}"),
+    ("statement.title", "Relevé d'activité de {0} - {1}", "Activity statement of {0} - {1}"),
+    ("statement.columns", "Date,CA,Heures,Dont hsup,Commentaire", "Date,Revenue,Hours,Of which overtime,Comment"),
+    ("statement.total", "Total", "Total"),
+    ("statement.prime_title", "Calcul de la prime", "Bonus computation"),
+    ("statement.worked_days", "Jours travaillés", "Worked days"),
+    ("statement.ca", "Chiffre d'affaire", "Revenue"),
+    ("statement.hours", "Heures", "Hours"),
+    ("statement.hsup", "Dont heures supplémentaires", "Of which overtime"),
+    ("statement.threshold", "Seuil de la prime", "Bonus threshold"),
+    ("statement.delta", "Delta", "Delta"),
+    ("statement.rate", "Taux de la prime", "Bonus rate"),
+    ("statement.prime", "Prime", "Bonus"),
+    ("holiday.new_year", "Jour de l'an", "New Year's Day"),
+    ("holiday.labour_day", "Fête du travail", "Labour Day"),
+    ("holiday.victory", "Victoire 1945", "Victory in Europe Day"),
+    ("holiday.national", "Fête nationale", "Bastille Day"),
+    ("holiday.assumption", "Assomption", "Assumption Day"),
+    ("holiday.all_saints", "Toussaint", "All Saints' Day"),
+    ("holiday.armistice", "Armistice 1918", "Armistice Day"),
+    ("holiday.christmas", "Noël", "Christmas Day"),
+    ("holiday.easter_monday", "Lundi de Pâques", "Easter Monday"),
+    ("holiday.ascension", "Ascension", "Ascension Day"),
+    ("holiday.whit_monday", "Lundi de Pentecôte", "Whit Monday"),
+    ("leave.paid", "congés payés", "paid leave"),
+    ("leave.sick", "maladie", "sick leave"),
+    ("leave.rtt", "RTT", "RTT day"),
+    ("leave.absence", "absence", "absence"),
+    ("ics.work", "Travail", "Work"),
+    ("ics.leave", "Congé", "Leave"),
+    ("ics.holiday", "Férié", "Public holiday"),
+    ("ics.summary", "CA {0} € - {1} h", "Revenue {0} € - {1} h"),
+    ("statement.no_pdf",
+        "fde a été compilé sans la fonctionnalité pdf (cargo build --features pdf)",
+        "fde was built without the pdf feature (cargo build --features pdf)"),
+    ("chart.days", "CA par jour", "Revenue per day"),
+    ("chart.months", "CA par mois", "Revenue per month"),
+    ("chart.weeks", "Heures par semaine", "Hours per week"),
+    ("chart.threshold",
+        "seuil de la prime : {0} €, atteint {1} mois sur {2}",
+        "bonus threshold : {0} €, reached {1} months out of {2}"),
+    ("chart.week", "{0} h (dont {1} hsup)", "{0} h ({1} overtime)"),
+    ("chart.week_no", "S{0}", "W{0}"),
+    ("svg.ca", "Chiffre d'affaire par mois", "Revenue per month"),
+    ("svg.ca_hour", "Chiffre d'affaire par heure", "Revenue per hour"),
+    ("svg.hsup", "Cumul des heures supplémentaires", "Cumulated overtime"),
+    ("svg.done", "les graphiques de {0} ont été écrits dans {1}", "the charts of {0} have been written to {1}"),
+    ("pay.title", "--- Estimation de la paie du mois {0} ({1}) ---", "--- Pay estimate for month {0} ({1}) ---"),
+    ("pay.normal", "Heures normales", "Normal hours"),
+    ("pay.overtime", "Heures sup. {0}", "Overtime {0}"),
+    ("pay.gross", "Salaire brut estimé", "Estimated gross pay"),
+    ("pay.no_wage",
+        "le taux horaire de {0} n'est pas renseigné, cf. fde profile {0} --wage=taux",
+        "the hourly wage of {0} is not set, see fde profile {0} --wage=wage"),
+    ("payslips.title", "--- Bulletins de paie de {0} : {1} ---", "--- Payslips of {0} : {1} ---"),
+    ("payslip.line",
+        "{0} : brut {1}, net {2}, prime {3}, heures sup. {4}",
+        "{0} : gross {1}, net {2}, bonus {3}, overtime {4}"),
+    ("payslip.saved", "le bulletin {0} a été enregistré", "the payslip {0} has been saved"),
+    ("reconcile.title",
+        "--- Rapprochement des bulletins de paie {0} ({1}) : {2} mois avec écart ---",
+        "--- Payslip reconciliation {0} ({1}) : {2} month(s) with a gap ---"),
+    ("reconcile.prime", "prime payée {0} au lieu de {1} ({2})", "bonus paid {0} instead of {1} ({2})"),
+    ("reconcile.overtime", "heures sup. payées {0} au lieu de {1} ({2})", "overtime paid {0} instead of {1} ({2})"),
+    ("col.month", "mois", "month"),
+    ("col.prime", "prime", "bonus"),
+    ("col.computed", "calculée", "computed"),
+    ("col.hsup", "hsup", "overtime"),
+    ("col.gross", "brut", "gross"),
+    ("col.estimated", "estimé", "estimated"),
+    ("summary.title", "--- Bilan ({0}, seuil {1}) ---", "--- Summary ({0}, threshold {1}) ---"),
+    ("summary.ca_avg", "CA moyen / mois saisi", "Avg revenue / month entered"),
+    ("summary.hours_avg", "Heures / mois saisi", "Avg hours / month entered"),
+    ("summary.hsup_avg", "HSup / mois saisi", "Avg overtime / month entered"),
+    ("summary.months", "Mois saisis", "Months entered"),
+    ("summary.above", "Mois > seuil", "Months > threshold"),
+    ("usage.compare",
+        "usage : fde compare <période A> <période B> [--tag=tag] (mm/yyyy, Tn/yyyy ou yyyy)",
+        "usage : fde compare <period A> <period B> [--tag=tag] (mm/yyyy, Tn/yyyy or yyyy)"),
+    ("usage.search", "usage : fde search <termes>", "usage : fde search <terms>"),
+    ("usage.history", "usage : fde history <date>", "usage : fde history <date>"),
+    ("usage.backup", "usage : fde backup [restore <fichier>]", "usage : fde backup [restore <file>]"),
+    ("usage.restore",
+        "usage : fde restore <date> <n° de la modification à annuler>",
+        "usage : fde restore <date> <n° of the change to undo>"),
+    ("usage.serve",
+        "usage : fde serve [--addr=ip:port] [--token=jeton]",
+        "usage : fde serve [--addr=ip:port] [--token=token]"),
+    ("usage.export",
+        "usage : fde export --format ics [--period=mm/yyyy, Tn/yyyy ou yyyy] [--output=fichier]",
+        "usage : fde export --format ics [--period=mm/yyyy, Tn/yyyy or yyyy] [--output=file]"),
+    ("usage.statement",
+        "usage : fde statement --month mm/yyyy [--output=fichier] [--pdf]",
+        "usage : fde statement --month mm/yyyy [--output=file] [--pdf]"),
+    ("usage.trash",
+        "usage : fde trash [restore <date> | days <nombre de jours> | purge]",
+        "usage : fde trash [restore <date> | days <number of days> | purge]"),
+    ("usage.trash_restore", "usage : fde trash restore <date>", "usage : fde trash restore <date>"),
+    ("usage.trash_days", "usage : fde trash days <nombre de jours>", "usage : fde trash days <number of days>"),
+    ("usage.chart",
+        "usage : fde chart [month mm/yyyy | year yyyy | weeks <période>] ou fde chart --svg fichier.svg [période]",
+        "usage : fde chart [month mm/yyyy | year yyyy | weeks <period>] or fde chart --svg file.svg [period]"),
+    ("usage.pay", "usage : fde pay [--month mm/yyyy]", "usage : fde pay [--month mm/yyyy]"),
+    ("usage.payslip",
+        "usage : fde payslip [--month mm/yyyy [--gross=brut] [--net=net] [--prime=prime] [--overtime=heures sup.]]",
+        "usage : fde payslip [--month mm/yyyy [--gross=gross] [--net=net] [--prime=bonus] [--overtime=overtime]]"),
+    ("usage.reconcile",
+        "usage : fde reconcile [période] (mm/yyyy, Tn/yyyy ou yyyy)",
+        "usage : fde reconcile [period] (mm/yyyy, Tn/yyyy or yyyy)"),
+    ("usage.summary", "usage : fde summary [mm/yyyy]", "usage : fde summary [mm/yyyy]"),
+    ("usage.profile",
+        "usage : fde profile [nom [--threshold=seuil] [--rate=taux en %] [--wage=taux horaire] [--overtime=heures:majoration en %,...,majoration en %]]",
+        "usage : fde profile [name [--threshold=threshold] [--rate=rate in %] [--wage=hourly wage] [--overtime=hours:increase in %,...,increase in %]]"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fill_in_one_pass() {
+        assert_eq!( fill( "{0} a {1}", &[&"x", &2] ), "x a 2" );
+        // le texte d'un argument n'est pas remplacé
+        assert_eq!( fill( "{0} / {1}", &[&"note {1}", &"b"] ), "note {1} / b" );
+        assert_eq!( fill( "{1}{0}{0}", &[&"a", &"b"] ), "baa" );
+        assert_eq!( fill( "{ {x} {2} {0", &[&"a"] ), "{ {x} {2} {0" );
+    }
+}
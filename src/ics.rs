@@ -0,0 +1,106 @@
+//! # ics :
+//! Ce module génère un calendrier iCalendar (RFC 5545) à partir des journées d'un profil : un
+//! évènement "journée entière" par jour, dans la catégorie travail ou congé (cf. fichier
+//! calendar.rs), ainsi qu'un évènement dans la catégorie férié pour chaque jour férié. Les titres
+//! et les catégories sont dans la langue de l'interface.
+//! - author : Thierry Probst <thierry.probst@free.fr>
+//! - version : 1.0.0
+//! - date : 19/10/2026
+use chrono::{Days, NaiveDate, Utc};
+use crate::ca::Ca;
+use crate::calendar;
+use crate::i18n::{self, msg};
+use crate::profile::Profile;
+
+
+/// calendar : le contenu du fichier .ics
+/// - params :
+///     - profile -> le profil concerné
+///     - days -> les journées du profil
+///     - holidays -> les jours fériés (date, nom)
+/// - return : le calendrier au format iCalendar
+pub fn calendar( profile: &Profile, days: &[Ca], holidays: &[(NaiveDate, String)] ) -> String {
+    let stamp = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//fde//fde 1.0.0//FR".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        format!( "X-WR-CALNAME:{}", escape_text( &format!( "fde - {}", profile.name ) ) ),
+    ];
+    let (work, leave_category, holiday) = (msg!( "ics.work" ), msg!( "ics.leave" ), msg!( "ics.holiday" ));
+    for c in days.iter() {
+        let Ok(date) = NaiveDate::parse_from_str( &c.date, "%Y-%m-%d" ) else { continue; };
+        let (summary, category) = match calendar::leave( &c.tags ) {
+            Some(calendar::PAID_LEAVE) => (leave_category.clone(), &leave_category),
+            Some(kind) => (format!( "{leave_category} ({})", i18n::tr( kind ) ), &leave_category),
+            None => (msg!( "ics.summary", i18n::decimal( c.ca ), i18n::decimal( c.hours ) ), &work),
+        };
+        let uid = format!( "{}-{}@fde", c.date, profile.id );
+        lines.extend( event( &uid, &stamp, &date, &summary, c.comment.as_deref(), category, &c.tags ) );
+    }
+    for (date, name) in holidays.iter() {
+        let uid = format!( "{date}-ferie@fde" );
+        lines.extend( event( &uid, &stamp, date, name, None, &holiday, &[] ) );
+    }
+    lines.push( "END:VCALENDAR".to_string() );
+    lines.iter().map( |l| fold( l ) ).collect::<Vec<String>>().join( "" )
+}
+
+/// event : les lignes d'un évènement "journée entière"
+/// - params :
+///     - uid -> l'identifiant unique de l'évènement
+///     - stamp -> l'horodatage de la génération du calendrier
+///     - date -> le jour concerné
+///     - summary -> le titre de l'évènement
+///     - description -> la description de l'évènement (optionnelle)
+///     - category -> la catégorie de l'évènement
+///     - tags -> les tags de la journée, ajoutés aux catégories
+/// - return : un Vecteur contenant les lignes de l'évènement
+fn event( uid: &str, stamp: &str, date: &NaiveDate, summary: &str, description: Option<&str>, category: &str,
+    tags: &[String] ) -> Vec<String> {
+    let categories: Vec<String> = std::iter::once( category.to_string() ).chain( tags.iter().cloned() )
+        .map( |c| escape_text( &c ) ).collect();
+    let mut v = vec![
+        "BEGIN:VEVENT".to_string(),
+        format!( "UID:{uid}" ),
+        format!( "DTSTAMP:{stamp}" ),
+        format!( "DTSTART;VALUE=DATE:{}", date.format("%Y%m%d") ),
+        format!( "DTEND;VALUE=DATE:{}", (*date + Days::new( 1 )).format("%Y%m%d") ),
+        format!( "SUMMARY:{}", escape_text( summary ) ),
+        format!( "CATEGORIES:{}", categories.join(",") ),
+        "TRANSP:TRANSPARENT".to_string(),
+    ];
+    if let Some(d) = description {
+        v.push( format!( "DESCRIPTION:{}", escape_text( d ) ) );
+    }
+    v.push( "END:VEVENT".to_string() );
+    v
+}
+
+/// escape_text : protège les caractères spéciaux d'une valeur TEXT iCalendar (\ ; , et retours à
+/// la ligne)
+/// - params : s -> le texte
+/// - return : le texte protégé
+fn escape_text( s: &str ) -> String {
+    s.replace( '\\', "\\\\" ).replace( ';', "\\;" ).replace( ',', "\\," ).replace( "\r\n", "\\n" ).replace( '\n', "\\n" )
+}
+
+/// fold : termine une ligne par CRLF en la coupant tous les 75 octets (sans couper un caractère),
+/// les lignes de continuation commencent par un espace
+/// - params : line -> la ligne
+/// - return : la ligne pliée
+fn fold( line: &str ) -> String {
+    let mut out = String::new();
+    let mut len = 0;
+    for c in line.chars() {
+        if len + c.len_utf8() > 75 {
+            out.push_str( "\r\n " );
+            len = 1;
+        }
+        out.push( c );
+        len += c.len_utf8();
+    }
+    out.push_str( "\r\n" );
+    out
+}
@@ -0,0 +1,61 @@
+//! # fde :
+//! la bibliothèque de fde : le suivi de l'activité de Nat (et d'autres personnes grâce aux
+//! profils). Elle contient le modèle de données (Ca, Entry, CaMonth, CaPeriod), l'accès au
+//! fichier sqlite3 (CaDaoSqlite), les règles de calcul (prime du profil, paie) et les rapports
+//! (graphiques, relevé, calendrier iCalendar, API REST). Le programme fde (cf. fichier main.rs)
+//! n'en est que l'interface en ligne de commande.
+//!
+//! L'erreur de la bibliothèque est error::Error, dont chaque sorte indique aussi le code de
+//! sortie du programme : les opérations (Ca::new, Ca::save, Server::run, ...) renvoient un
+//! error::Result, et l'opérateur ? convertit en Error::Db les erreurs des requêtes sqlite3
+//! (cf. fichier error.rs).
+//!
+//! ```no_run
+//! use chrono::NaiveDate;
+//! use fde::{CaDaoSqlite, CaMonth};
+//!
+//! let sqlite = CaDaoSqlite::new( "./data/fildeclair.sq3", fde::profile::DEFAULT_PROFILE );
+//! let profile = sqlite.profile( None )?;
+//! let mut month = CaMonth::new( NaiveDate::from_ymd_opt( 2024, 5, 1 ).unwrap(), &profile, &[] );
+//! month.retrieve_with( &sqlite )?;
+//! println!( "{} : {:.2} €, prime {:.2} €", profile.name, month.ca, month.prime() );
+//! # Ok::<(), fde::Error>(())
+//! ```
+//! - author : Thierry Probst <thierry.probst@free.fr>
+//! - version : 1.0.0
+//! - date : 19/10/2026
+
+pub mod backup;
+pub mod ca;
+pub mod cadaosqlite;
+pub mod calendar;
+pub mod camonth;
+pub mod caperiod;
+pub mod chart;
+pub mod dateexpr;
+pub mod doctor;
+pub mod entry;
+pub mod error;
+pub mod history;
+pub mod i18n;
+pub mod ics;
+mod markup;
+pub mod pay;
+pub mod period;
+pub mod profile;
+pub mod schema;
+pub mod server;
+pub mod statement;
+pub mod summary;
+pub mod svg;
+pub mod tag;
+mod web;
+
+pub use ca::Ca;
+pub use cadaosqlite::CaDaoSqlite;
+pub use camonth::CaMonth;
+pub use caperiod::CaPeriod;
+pub use entry::Entry;
+pub use error::Error;
+pub use period::Period;
+pub use profile::Profile;
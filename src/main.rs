@@ -1,20 +1,21 @@
 //! # fde :
-//! un programe pour suivre l'activité de Nat
+//! un programe pour suivre l'activité de Nat (et d'autres personnes grâce aux profils)
 //! - author : Thierry Probst <thierry.probst@free.fr>
 //! - version : 1.0.0
 //! - date : 23/04/2023
 
 use std::env;
-use chrono::{Local, NaiveDate};
-use dialog::Dialog;
-use camonth::CaMonth;
-use cadaosqlite::CaDaoSqlite;
-use ca::Ca;
-
-mod ca;
-mod cadaosqlite;
+use chrono::{Datelike, Local, Months, NaiveDate};
+use dialog::{Dialog, HIGHLIGHT_START, HIGHLIGHT_END};
+use fde::{backup, calendar, chart, dateexpr, i18n, ics, server, statement, summary, svg, tag};
+use fde::{Ca, CaDaoSqlite, CaMonth, CaPeriod};
+use fde::error::{Error, Result};
+use fde::i18n::{msg, Lang};
+use fde::period::{Period, Step};
+use fde::pay::{Bracket, Pay, Payslip, Reconciliation, DEFAULT_OVERTIME};
+use fde::profile::{Profile, DEFAULT_PROFILE, DEFAULT_RATE, DEFAULT_THRESHOLD};
+
 mod dialog;
-mod camonth;
 
 const AUTHOR: &str = "Thierry Probst <thierry.probst@free.fr>";
 const VERSION: &str = "1.0.0";
@@ -26,65 +27,721 @@ const DB_URL: &str = "./data/fildeclair.sq3";
 /// vers la bonne fonction. Actuellement les options supportées sont :
 /// 1. l'affichage d'un écran d'aide
 /// 2. l'affichage des données d'un mois particulier (indiqué en parametre)
+/// 3. la saisie des données d'un jour particulier (indiqué en parametre)
+///
+/// Le premier argument peut aussi être une commande (compare, ...) suivie de ses propres
+/// arguments. L'option --profile=nom, valable pour toutes les commandes, choisit la personne
+/// concernée (le profil par défaut sinon), l'option --lang=fr|en choisit la langue de l'interface.
+///
+/// Une erreur interrompt la commande : son message est affiché et le programme se termine avec le
+/// code de sortie de l'erreur (cf. fichier error.rs).
+fn main() {
+    if let Err(e) = run() {
+        eprintln!( "{e}" );
+        std::process::exit( e.exit_code() );
+    }
+}
 
-fn main() -> () {
-    let args: Vec<String> = env::args().collect();
-    let today = Local::now().date_naive();
+/// run : lit la ligne de commande et exécute la commande demandée (cf. fonction main)
+/// - params : aucun
+/// - return : l'erreur qui a interrompu la commande
+fn run() -> Result<()> {
+    let all: Vec<String> = env::args().collect();
+    let Some(lang) = Lang::select( &all ) else {
+        return Err( Error::Parse( "usage : --lang=fr|en".to_string() ) );
+    };
+    i18n::init( lang );
+    let selector = Profile::selector( &all );
+    let args: Vec<String> = all.into_iter().filter( |a| !a.starts_with("--profile=") && !a.starts_with("--lang=") ).collect();
+
+    Dialog::show_welcome();
+    let sqlite = CaDaoSqlite::new( DB_URL, DEFAULT_PROFILE );
+    let profile = match sqlite.profile( selector.as_deref() ) {
+        Ok(p) => p,
+        Err(rusqlite::Error::QueryReturnedNoRows) =>
+            return Err( Error::Config( msg!( "profile.unknown", selector.unwrap_or_default() ) ) ),
+        Err(e) => return Err( e.into() ),
+    };
+    match args.get(1).map( |s| s.as_str() ) {
+        Some("compare") => compare( &profile, &args[2..] ),
+        Some("search") => search( &profile, &args[2..] ),
+        Some("profile") => manage_profiles( &args[2..] ),
+        Some("history") => history( &profile, &args[2..] ),
+        Some("backup") => match args.get(2).map( |s| s.as_str() ) {
+            None => make_backup(),
+            Some("restore") => restore_backup( &args[3..] ),
+            _ => Err( Error::Parse( msg!( "usage.backup" ) ) ),
+        },
+        Some("doctor") => check_database( &args[2..] ),
+        Some("serve") => serve( &profile, &args[2..] ),
+        Some("export") => export( &profile, &args[2..] ),
+        Some("statement") => make_statement( &profile, &args[2..] ),
+        Some("restore") => restore( &profile, &args[2..] ),
+        Some("trash") => trash( &profile, &args[2..] ),
+        Some("chart") => make_chart( &profile, &args[2..] ),
+        Some("pay") => pay( &profile, &args[2..] ),
+        Some("payslip") => payslip( &profile, &args[2..] ),
+        Some("reconcile") => reconcile( &profile, &args[2..] ),
+        Some("summary") => summary( &profile, &args[2..] ),
+        _ => process_options( &profile, &args[1..] ),
+    }
+}
+
+/// process_options : traite les options de la ligne de commande les unes après les autres
+/// - params :
+///     - profile -> le profil concerné
+///     - args -> les arguments de la ligne de commande (sans le nom du programme)
+/// - return : l'erreur Parse si une option est inconnue, ou l'erreur de la session
+fn process_options(profile: &Profile, args: &[String]) -> Result<()> {
+    let tags = tag::filters( args );
 
     for arg in args.iter() {
         match arg.as_str() {
-            x if x.contains("fde") => Dialog::show_welcome(),
+            x if x.starts_with("--tag=") => (),
             x if x.contains("--help") | x.contains("-h") => Dialog::show_help(),
             x if x.contains("--month") | x.contains("-m") => {
-                match NaiveDate::parse_from_str( extract_date(&x), "%d/%m/%Y" ) {
-                    Ok(x) => get_month( x ),
-                    Err(_) => get_month( today ),
+                match Period::parse( extract_date(x) ).filter( |p| p.nb_months == 1 ) {
+                    Some(p) => session( Screen::Month( p.start ), profile, &tags )?,
+                    None if !x.contains('=') => session( Screen::Month( dateexpr::today() ), profile, &tags )?,
+                    None => return Err( Error::Parse( msg!( "date.invalid" ) ) ),
                 }
             },
             x if x.contains("--day") | x.contains("-d") => {
-                match NaiveDate::parse_from_str( extract_date(&x), "%d/%m/%Y" ) {
-                    Ok(x) => process_day( x ),
-                    Err(_) => println!("erreur dans NaiveDate::parse_from_str ..."),
+                match dateexpr::parse( extract_date(x) ) {
+                    Some(x) => session( Screen::Day( x ), profile, &tags )?,
+                    None => return Err( Error::Parse( msg!( "date.invalid" ) ) ),
                 }
             },
-            _ => println!("commande inconnue! fin du programme ..."),
+            _ => return Err( Error::Parse( msg!( "command.unknown" ) ) ),
         }
     }
+    Ok(())
+}
+
+/// les écrans d'une session interactive (cf. fonction session)
+enum Screen {
+    /// le menu d'un jour
+    Day(NaiveDate),
+    /// le rapport d'un mois, représenté par un de ses jours
+    Month(NaiveDate),
+}
+
+/// session : enchaîne les écrans jour et mois jusqu'à ce que l'utilisateur quitte, avec une seule
+/// connexion sur le fichier sqlite3. Depuis un jour, [n] et [p] passent au jour suivant ou
+/// précédent et [M] ouvre son mois ; depuis un mois, un numéro de jour ouvre ce jour.
+/// - params :
+///     - start -> le premier écran affiché
+///     - profile -> le profil concerné
+///     - tags -> les tags auxquels limiter le rapport des mois (vide pour tout le mois)
+/// - return : l'erreur qui a interrompu la session
+fn session(start: Screen, profile: &Profile, tags: &[String]) -> Result<()> {
+    let sqlite = CaDaoSqlite::new( DB_URL, profile.id ).open_session()?;
+    let mut screen = Some( start );
+    while let Some(s) = screen {
+        screen = match s {
+            Screen::Day(day) => process_day( &sqlite, day )?,
+            Screen::Month(day) => get_month( &sqlite, day, profile, tags )?,
+        };
+    }
+    Ok(())
 }
 
 /// process_day : récupère et affiche les informations liées à un jour particulier (représenté par
-/// une date)
-/// - params : day -> un jour qui servira de base pour déterminer le mois choisi
-/// - return : un Result vide sinon les Erreurs sqlite
-fn process_day(day: NaiveDate) -> () {
-    let mut c = Ca::new( &day );
+/// une date), puis applique le choix fait dans le menu
+/// - params :
+///     - sqlite -> le CaDaoSqlite de la session
+///     - day -> le jour concerné
+/// - return : l'écran suivant, None pour quitter, sinon l'erreur de lecture ou d'enregistrement
+fn process_day(sqlite: &CaDaoSqlite, day: NaiveDate) -> Result<Option<Screen>> {
+    // un jour absent de la DB n'y est écrit qu'une fois modifié ou sauvegardé
+    let created = !sqlite.exist( &day )?;
+    let c = Ca::new( sqlite, &day )?;
     loop {
-        match Dialog::menu_ca( &c ).as_str() { // on affiche le menu
-            "d" => { c.delete(); break; }, // delete
-            "m" => { c = Dialog::dialog_ca( c ); c.save(); break; }, // modify
-            "s" => { c.save(); break; }, // save
-            "q" => break,
-            _ => println!( "option inconnue" ),
+        let c = match Dialog::menu_ca( &c ).as_str() { // on affiche le menu
+            "d" if created => return Ok( Some( Screen::Month( day ) ) ), // rien à effacer
+            "d" if Dialog::confirm_delete( &c )? => { // delete (soft)
+                c.delete( sqlite )?;
+                return Ok( Some( Screen::Month( day ) ) );
+            },
+            "d" => continue,
+            "m" => Dialog::dialog_ca( c )?, // modify
+            "a" => Dialog::dialog_add_entry( c )?, // add an activity
+            "r" if created => { println!( "{}", msg!( "entry.only_one" ) ); continue; },
+            "r" => Dialog::dialog_remove_entry( c )?, // remove an activity
+            "s" => c, // save
+            "n" => return Ok( day.succ_opt().map( Screen::Day ) ),
+            "p" => return Ok( day.pred_opt().map( Screen::Day ) ),
+            "M" => return Ok( Some( Screen::Month( day ) ) ),
+            "q" => return Ok( None ),
+            _ => { println!( "{}", msg!( "option.unknown" ) ); continue; },
+        };
+        c.save( sqlite )?;
+        if created { println!( "{}", msg!( "ca.created", c ) ); }
+        // après une modification le jour est relu, pour récupérer les identifiants des activités
+        return Ok( Some( Screen::Day( day ) ) );
+    }
+}
+
+/// get_month : récupère et affiche les informations liées à un mois particulier (représenté par
+/// une date), puis propose d'ouvrir un de ses jours
+/// - params :
+///     - sqlite -> le CaDaoSqlite de la session
+///     - day -> un jour qui servira de base pour déterminer le mois choisi
+///     - profile -> le profil concerné
+///     - tags -> les tags auxquels limiter le rapport (vide pour tout le mois)
+/// - return : l'écran suivant, None pour quitter, sinon l'erreur de lecture
+fn get_month(sqlite: &CaDaoSqlite, day: NaiveDate, profile: &Profile, tags: &[String]) -> Result<Option<Screen>> {
+    let mut cmonth = CaMonth::new( day, profile, tags );
+    cmonth.retrieve_with( sqlite )?;
+    let first = day.with_day( 1 ).unwrap();
+    Ok( match Dialog::menu_month( cmonth ).as_str() {
+        "n" => Some( Screen::Month( first + Months::new( 1 ) ) ),
+        "p" => Some( Screen::Month( first - Months::new( 1 ) ) ),
+        "q" => None,
+        x => match x.parse().ok().and_then( |d| first.with_day( d ) ) {
+            Some(d) => Some( Screen::Day( d ) ),
+            None => { println!( "{}", msg!( "option.unknown" ) ); Some( Screen::Month( day ) ) },
+        },
+    } )
+}
+
+/// compare : affiche la comparaison de deux périodes
+/// - params :
+///     - profile -> le profil concerné
+///     - args -> les arguments de la commande (période A, période B et filtres --tag)
+/// - return : l'erreur Parse si les périodes sont invalides, ou l'erreur de lecture
+fn compare(profile: &Profile, args: &[String]) -> Result<()> {
+    let tags = tag::filters( args );
+    let args: Vec<&String> = args.iter().filter( |a| !a.starts_with("--tag=") ).collect();
+    let periods: Vec<Period> = args.iter().filter_map( |a| Period::parse( a ) ).collect();
+    if args.len() != 2 || periods.len() != 2 {
+        return Err( Error::Parse( msg!( "usage.compare" ) ) );
+    }
+    let mut a = CaPeriod::new( periods[0].clone(), profile, &tags );
+    let mut b = CaPeriod::new( periods[1].clone(), profile, &tags );
+    a.retrieve_datas( DB_URL.to_string() )?;
+    b.retrieve_datas( DB_URL.to_string() )?;
+    Dialog::show_compare( &a, &b );
+    Ok(())
+}
+
+/// search : affiche les jours dont le commentaire contient les termes recherchés
+/// - params :
+///     - profile -> le profil concerné
+///     - args -> les termes recherchés
+/// - return : l'erreur Parse sans terme, ou l'erreur de lecture
+fn search(profile: &Profile, args: &[String]) -> Result<()> {
+    if args.is_empty() {
+        return Err( Error::Parse( msg!( "usage.search" ) ) );
+    }
+    let sqlite = CaDaoSqlite::new( DB_URL, profile.id );
+    Dialog::show_search( args, &sqlite.search( args, HIGHLIGHT_START, HIGHLIGHT_END )? );
+    Ok(())
+}
+
+/// history : affiche l'historique des modifications d'un jour
+/// - params :
+///     - profile -> le profil concerné
+///     - args -> les arguments de la commande (la date, cf. dateexpr::parse)
+/// - return : l'erreur Parse si la date est invalide, ou l'erreur de lecture
+fn history(profile: &Profile, args: &[String]) -> Result<()> {
+    let Some(day) = args.first().and_then( |a| dateexpr::parse( a ) ) else {
+        return Err( Error::Parse( msg!( "usage.history" ) ) );
+    };
+    let sqlite = CaDaoSqlite::new( DB_URL, profile.id );
+    Dialog::show_history( &day, &sqlite.history( &day )? );
+    Ok(())
+}
+
+/// restore : annule une modification de l'historique d'un jour
+/// - params :
+///     - profile -> le profil concerné
+///     - args -> les arguments de la commande (la date, cf. dateexpr::parse, et le n° de la
+///       modification à annuler, affiché par fde history)
+/// - return : l'erreur Parse si les arguments sont invalides ou si la modification concerne un
+///   autre jour, sinon l'erreur de lecture ou d'enregistrement
+fn restore(profile: &Profile, args: &[String]) -> Result<()> {
+    let day = args.first().and_then( |a| dateexpr::parse( a ) );
+    let id = args.get(1).and_then( |a| a.parse::<i64>().ok() );
+    let (Some(day), Some(id)) = (day, id) else {
+        return Err( Error::Parse( msg!( "usage.restore" ) ) );
+    };
+    let sqlite = CaDaoSqlite::new( DB_URL, profile.id );
+    if !sqlite.history( &day )?.iter().any( |h| h.id == id ) {
+        return Err( Error::Parse( msg!( "restore.other_day", id, i18n::date( &day ) ) ) );
+    }
+    let h = sqlite.restore( id )?;
+    println!( "{}", msg!( "restore.done", h.id, h.op, i18n::timestamp( &h.ts ) ) );
+    Ok(())
+}
+
+/// make_backup : sauvegarde le fichier sqlite3 dans le répertoire des sauvegardes
+/// - params : aucun
+/// - return : l'erreur de la sauvegarde
+fn make_backup() -> Result<()> {
+    let file = backup::backup( &rusqlite::Connection::open( DB_URL )?, "manual" )?;
+    println!( "{}", msg!( "backup.done", file.display(), backup::BACKUP_KEEP ) );
+    Ok(())
+}
+
+/// restore_backup : remplace le fichier sqlite3 par une sauvegarde, après avoir vérifié son schéma
+/// - params : args -> les arguments de fde backup restore (le chemin de la sauvegarde)
+/// - return : l'erreur Parse sans chemin, Io si la sauvegarde n'existe pas, Validation si son
+///   schéma n'est pas valide (avec la liste des problèmes), sinon l'erreur de la restauration
+fn restore_backup(args: &[String]) -> Result<()> {
+    let [file] = args else {
+        return Err( Error::Parse( msg!( "usage.backup" ) ) );
+    };
+    if !std::path::Path::new( file ).is_file() {
+        return Err( Error::Io( std::io::Error::new( std::io::ErrorKind::NotFound, msg!( "file.missing", file ) ) ) );
+    }
+    match backup::restore( DB_URL, file )? {
+        (_, Some(saved)) => println!( "{}", msg!( "backup.restored", file, saved.display() ) ),
+        (problems, None) => {
+            let problems: String = problems.iter().map( |p| format!( "\n\t{p}" ) ).collect();
+            return Err( Error::Validation( format!( "{}{problems}", msg!( "backup.not_restored", file ) ) ) );
+        },
+    }
+    Ok(())
+}
+
+/// check_database : vérifie l'intégrité du fichier sqlite3, affiche les anomalies des entrées et
+/// répare celles qui peuvent l'être si l'option --repair est présente
+/// - params : args -> les arguments de la commande (--repair)
+/// - return : l'erreur de lecture ou de réparation
+fn check_database(args: &[String]) -> Result<()> {
+    let repair = args.iter().any( |a| a == "--repair" );
+    let sqlite = CaDaoSqlite::new( DB_URL, DEFAULT_PROFILE );
+    let integrity = sqlite.integrity_check()?;
+    let anomalies = sqlite.anomalies( Local::now().date_naive() )?;
+    Dialog::show_doctor( &integrity, &anomalies );
+    if repair {
+        println!( "{}", msg!( "doctor.repaired", sqlite.repair( &anomalies )? ) );
+    } else if anomalies.iter().any( |a| a.repair.is_some() ) {
+        println!( "{}", msg!( "doctor.use_repair" ) );
+    }
+    Ok(())
+}
+
+/// serve : démarre le serveur http de l'API REST (cf. fichier server.rs)
+/// - params :
+///     - profile -> le profil par défaut des requêtes
+///     - args -> les arguments de la commande (--addr=ip:port et --token=jeton, le jeton peut
+///       aussi être donné par la variable d'environnement FDE_TOKEN)
+/// - return : l'erreur Parse si les arguments sont invalides, sinon l'erreur du démarrage du
+///   serveur (cf. Server::run)
+fn serve(profile: &Profile, args: &[String]) -> Result<()> {
+    let mut addr = server::DEFAULT_ADDR.to_string();
+    let mut token = env::var( "FDE_TOKEN" ).ok().filter( |t| !t.is_empty() );
+    for arg in args.iter() {
+        match arg.split_once('=') {
+            Some(("--addr", a)) => addr = a.to_string(),
+            Some(("--token", t)) if !t.is_empty() => token = Some( t.to_string() ),
+            _ => return Err( Error::Parse( msg!( "usage.serve" ) ) ),
         }
+    }
+    server::Server::new( DB_URL, profile, token ).run( &addr )
+}
 
+/// export : exporte les journées d'un profil dans un fichier. Le seul format actuel est ics
+/// (iCalendar) : un évènement par jour travaillé ou de congé, et les jours fériés.
+/// - params :
+///     - profile -> le profil concerné
+///     - args -> les arguments de la commande (--format ics, --period=période pour limiter
+///       l'export, --output=fichier)
+/// - return : l'erreur Parse si les arguments sont invalides, sinon l'erreur de lecture ou
+///   d'écriture du fichier
+fn export(profile: &Profile, args: &[String]) -> Result<()> {
+    let usage = msg!( "usage.export" );
+    let mut format = None;
+    let mut period = None;
+    let mut output = "fde.ics".to_string();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.split_once('=') {
+            Some(("--format", f)) => format = Some( f.to_string() ),
+            Some(("--period", p)) => match Period::parse( p ) {
+                Some(p) => period = Some( p ),
+                None => return Err( Error::Parse( usage ) ),
+            },
+            Some(("--output", o)) => output = o.to_string(),
+            None if arg == "--format" => format = iter.next().cloned(),
+            _ => return Err( Error::Parse( usage ) ),
+        }
+    }
+    if format.as_deref() != Some("ics") {
+        return Err( Error::Parse( usage ) );
     }
+
+    let (start, end) = match &period {
+        Some(p) => (p.start, p.end()),
+        None => (NaiveDate::from_ymd_opt( 1, 1, 1 ).unwrap(), NaiveDate::from_ymd_opt( 9999, 12, 31 ).unwrap()),
+    };
+    let sqlite = CaDaoSqlite::new( DB_URL, profile.id );
+    let days = sqlite.days( &start, &end )?;
+    // les jours fériés des années de la période, ou des années qui contiennent des données
+    let data_years: Vec<i32> = days.iter().filter_map( |c| NaiveDate::parse_from_str( &c.date, "%Y-%m-%d" ).ok() )
+        .map( |d| d.year() ).collect();
+    let years: Vec<i32> = match (&period, data_years.iter().min(), data_years.iter().max()) {
+        (Some(p), _, _) => (p.start.year()..=(p.end() - chrono::Days::new( 1 )).year()).collect(),
+        (None, Some(first), Some(last)) => (*first..=*last).collect(),
+        _ => Vec::new(),
+    };
+    let holidays: Vec<(NaiveDate, String)> = years.into_iter().flat_map( calendar::holidays )
+        .filter( |(d, _)| *d >= start && *d < end ).collect();
+
+    std::fs::write( &output, ics::calendar( profile, &days, &holidays ) ).map_err( |e| Error::file( &output, e ) )?;
+    println!( "{}", msg!( "export.done", days.len(), holidays.len(), output ) );
+    Ok(())
 }
 
-/// get_month : récupère et affiche les informations liées à un mois particulier (représenté par
-/// une date)
-/// - params : day -> un jour qui servira de base pour déterminer le mois choisi
-/// - return : valeur de retour vide
-fn get_month(day: NaiveDate) -> () {
-    let mut cmonth = CaMonth{ day: day, ca: 0.0, hours:0.0, hsup: 0.0, datas: Vec::new() };
-    match cmonth.retrieve_datas( DB_URL.to_string()) {
-        Ok(()) => Dialog::show_month( cmonth ),
-        Err(e) => Dialog::something_goes_wrong( "CaMonth::retrieve_datas", e),
+/// make_statement : écrit le relevé mensuel d'un profil (HTML, ou PDF avec l'option --pdf)
+/// - params :
+///     - profile -> le profil concerné
+///     - args -> les arguments de la commande (--month mm/yyyy, --output=fichier et --pdf)
+/// - return : l'erreur Parse si les arguments sont invalides, sinon l'erreur de lecture ou
+///   d'écriture du relevé
+fn make_statement(profile: &Profile, args: &[String]) -> Result<()> {
+    let usage = msg!( "usage.statement" );
+    let mut month = None;
+    let mut output = None;
+    let mut pdf = false;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.split_once('=') {
+            Some(("--month", m)) | Some(("-m", m)) => month = Period::parse( m ),
+            Some(("--output", o)) => output = Some( o.to_string() ),
+            None if arg == "--month" || arg == "-m" => month = iter.next().and_then( |m| Period::parse( m ) ),
+            None if arg == "--pdf" => pdf = true,
+            _ => return Err( Error::Parse( usage ) ),
+        }
+    }
+    let Some(month) = month.filter( |p| p.nb_months == 1 ) else {
+        return Err( Error::Parse( usage ) );
+    };
+
+    let mut cmonth = CaMonth::new( month.start, profile, &[] );
+    cmonth.retrieve_datas( DB_URL.to_string() )?;
+    let extension = if pdf { "pdf" } else { "html" };
+    let output = output.unwrap_or( format!( "releve-{}-{}.{extension}", profile.name, month.start.format("%Y-%m") ) );
+    let content = if pdf { statement_pdf( &cmonth )? } else { statement::html( &cmonth ).into_bytes() };
+    std::fs::write( &output, content ).map_err( |e| Error::file( &output, e ) )?;
+    println!( "{}", msg!( "statement.done", month, output ) );
+    Ok(())
+}
+
+/// statement_pdf : le relevé mensuel au format PDF
+/// - params : m -> le mois concerné
+/// - return : le contenu du fichier, ou l'erreur Io si le PDF n'a pas pu être construit
+#[cfg(feature = "pdf")]
+fn statement_pdf(m: &CaMonth) -> Result<Vec<u8>> {
+    statement::pdf( m ).map_err( |e| Error::Io( std::io::Error::other( msg!( "statement.failed", e ) ) ) )
+}
+
+/// statement_pdf : le relevé mensuel au format PDF
+/// - params : m -> le mois concerné
+/// - return : l'erreur Config, car fde a été compilé sans la fonctionnalité pdf
+#[cfg(not(feature = "pdf"))]
+fn statement_pdf(_m: &CaMonth) -> Result<Vec<u8>> {
+    Err( Error::Config( msg!( "statement.no_pdf" ) ) )
+}
+
+/// trash : affiche les jours effacés, restaure un jour effacé, change la durée de conservation
+/// des jours effacés ou supprime définitivement ceux qui l'ont dépassée
+/// - params :
+///     - profile -> le profil concerné
+///     - args -> vide pour la liste, sinon restore <dd/mm/yyyy>, days <nombre de jours> ou purge
+/// - return : l'erreur Parse si les arguments sont invalides, ou l'erreur de lecture ou
+///   d'enregistrement
+fn trash(profile: &Profile, args: &[String]) -> Result<()> {
+    let sqlite = CaDaoSqlite::new( DB_URL, profile.id );
+    let arg = args.get(1);
+    match args.first().map( |s| s.as_str() ) {
+        None => Dialog::show_trash( &sqlite.trash()?, sqlite.trash_days()? ),
+        Some("restore") => match arg.and_then( |a| dateexpr::parse( a ) ) {
+            Some(day) => match sqlite.untrash( &day )? {
+                0 => println!( "{}", msg!( "trash.none", i18n::date( &day ) ) ),
+                nb => println!( "{}", msg!( "trash.restored", i18n::date( &day ), nb ) ),
+            },
+            None => return Err( Error::Parse( msg!( "usage.trash_restore" ) ) ),
+        },
+        Some("days") => match arg.and_then( |a| a.parse::<i64>().ok() ).filter( |d| *d >= 0 ) {
+            Some(days) => {
+                sqlite.set_trash_days( days )?;
+                println!( "{}", msg!( "trash.days", days ) );
+            },
+            None => return Err( Error::Parse( msg!( "usage.trash_days" ) ) ),
+        },
+        Some("purge") if arg.is_none() => println!( "{}", msg!( "trash.purged", sqlite.purge()? ) ),
+        _ => return Err( Error::Parse( msg!( "usage.trash" ) ) ),
+    }
+    Ok(())
+}
+
+/// make_chart : affiche un graphique en barres dans le terminal, ou écrit les graphiques du
+/// bilan annuel dans un fichier SVG
+/// - params :
+///     - profile -> le profil concerné
+///     - args -> les arguments de la commande : month mm/yyyy (CA par jour), year yyyy (CA par
+///       mois, par défaut) ou weeks <période> (heures par semaine). sans date, la période
+///       courante. --svg fichier [période] pour le fichier SVG
+/// - return : l'erreur Parse si les arguments sont invalides, sinon l'erreur de lecture ou
+///   d'écriture du fichier
+fn make_chart(profile: &Profile, args: &[String]) -> Result<()> {
+    let usage = msg!( "usage.chart" );
+    let today = Local::now().date_naive();
+    let mut svg = None;
+    let mut rest = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.split_once('=') {
+            Some(("--svg", f)) => svg = Some( f.to_string() ),
+            None if arg == "--svg" => svg = iter.next().cloned(),
+            _ => rest.push( arg.clone() ),
+        }
+    }
+    if let Some(output) = svg {
+        return match rest.iter().filter( |a| *a != "year" ).map( |a| Period::parse( a ) ).next() {
+            Some(Some(period)) => chart_svg( profile, &period, &output ),
+            None => chart_svg( profile, &Period::year( today.year() ).unwrap(), &output ),
+            Some(None) => Err( Error::Parse( usage ) ),
+        };
+    }
+    let args = rest;
+    let kind = args.first().map( |s| s.as_str() ).unwrap_or( "year" );
+    let period = match (kind, args.get(1)) {
+        (_, Some(p)) => Period::parse( p ),
+        ("year", None) => Period::year( today.year() ),
+        (_, None) => Period::month( today.year(), today.month() ),
+    };
+    let (step, title) = match (kind, &period) {
+        ("month", Some(p)) if p.nb_months == 1 => (Step::Day, msg!( "chart.days" )),
+        ("year", Some(_)) => (Step::Month, msg!( "chart.months" )),
+        ("weeks", Some(_)) => (Step::Week, msg!( "chart.weeks" )),
+        _ => return Err( Error::Parse( usage ) ),
+    };
+    let Some(period) = period else { return Err( Error::Parse( usage ) ); };
+
+    // les semaines commencent le lundi, même si la période commence un autre jour
+    let start = step.first( period.start );
+    let end = match step.first( period.end() ) {
+        d if d < period.end() => step.next( d ),
+        d => d,
+    };
+    let sqlite = CaDaoSqlite::new( DB_URL, profile.id );
+    let totals = step.fill( start, end, &sqlite.totals( &start, &end, step )? );
+    let chart = match step {
+        Step::Day => chart::days( &totals ),
+        Step::Week => chart::weeks( &totals ),
+        Step::Month => chart::months( &totals, profile.threshold ),
+    };
+    Dialog::show_chart( &format!( "{title} - {period} ({})", profile.name ), &chart );
+    Ok(())
+}
+
+/// chart_svg : écrit les graphiques du bilan d'une période dans un fichier SVG : le CA de chaque
+/// mois comparé au seuil de la prime, le CA par heure et le cumul des heures supplémentaires
+/// - params :
+///     - profile -> le profil concerné
+///     - period -> la période concernée
+///     - output -> le nom du fichier
+/// - return : l'erreur de lecture ou d'écriture du fichier
+fn chart_svg(profile: &Profile, period: &Period, output: &str) -> Result<()> {
+    let sqlite = CaDaoSqlite::new( DB_URL, profile.id );
+    let totals = Step::Month.fill( period.start, period.end(), &sqlite.totals( &period.start, &period.end(), Step::Month )? );
+    std::fs::write( output, svg::charts( &period.to_string(), profile, &totals ) ).map_err( |e| Error::file( output, e ) )?;
+    println!( "{}", msg!( "svg.done", period, output ) );
+    Ok(())
+}
+
+/// pay : affiche l'estimation de la paie d'un mois (heures normales, heures supplémentaires
+/// majorées, prime et salaire brut)
+/// - params :
+///     - profile -> le profil concerné
+///     - args -> les arguments de la commande (--month mm/yyyy, le mois en cours par défaut)
+/// - return : l'erreur Parse si les arguments sont invalides, Config si le taux horaire du
+///   profil n'est pas défini, sinon l'erreur de lecture
+fn pay(profile: &Profile, args: &[String]) -> Result<()> {
+    let usage = msg!( "usage.pay" );
+    let today = Local::now().date_naive();
+    let mut month = Period::month( today.year(), today.month() );
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.split_once('=') {
+            Some(("--month", m)) | Some(("-m", m)) => month = Period::parse( m ),
+            None if arg == "--month" || arg == "-m" => month = iter.next().and_then( |m| Period::parse( m ) ),
+            _ => return Err( Error::Parse( usage ) ),
+        }
+    }
+    let Some(month) = month.filter( |p| p.nb_months == 1 ) else {
+        return Err( Error::Parse( usage ) );
+    };
+    if profile.wage <= 0.0 {
+        return Err( Error::Config( msg!( "pay.no_wage", profile.name ) ) );
+    }
+
+    let mut cmonth = CaMonth::new( month.start, profile, &[] );
+    cmonth.retrieve_datas( DB_URL.to_string() )?;
+    let sqlite = CaDaoSqlite::new( DB_URL, profile.id );
+    Dialog::show_pay( &cmonth, &estimate_pay( &sqlite, profile, &month.start, &month.end(), cmonth.prime() )? );
+    Ok(())
+}
+
+/// estimate_pay : estime la paie d'un mois à partir des totaux de ses semaines, limitées aux
+/// jours du mois et entières (cf. Pay::estimate)
+/// - params :
+///     - sqlite -> le CaDaoSqlite du profil
+///     - profile -> le profil concerné
+///     - start, end -> le premier jour du mois et le premier jour du mois suivant
+///     - prime -> la prime du mois
+/// - return : la paie estimée, sinon l'erreur de lecture
+fn estimate_pay(sqlite: &CaDaoSqlite, profile: &Profile, start: &NaiveDate, end: &NaiveDate, prime: f64) -> Result<Pay> {
+    let weeks = sqlite.totals( start, end, Step::Week )?;
+    let (first, last) = Pay::whole_weeks( start, end );
+    let whole = sqlite.totals( &first, &last, Step::Week )?;
+    Ok( Pay::estimate( profile, &weeks, &whole, prime ) )
+}
+
+/// payslip : affiche les bulletins de paie enregistrés, ou enregistre les montants payés pour un
+/// mois (les montants absents gardent leur valeur précédente)
+/// - params :
+///     - profile -> le profil concerné
+///     - args -> vide pour la liste, sinon --month mm/yyyy suivi des montants --gross=brut,
+///       --net=net, --prime=prime et --overtime=heures sup.
+/// - return : l'erreur Parse si les arguments sont invalides, ou l'erreur de lecture ou
+///   d'enregistrement
+fn payslip(profile: &Profile, args: &[String]) -> Result<()> {
+    let usage = || Error::Parse( msg!( "usage.payslip" ) );
+    let sqlite = CaDaoSqlite::new( DB_URL, profile.id );
+    if args.is_empty() {
+        let (start, end) = (NaiveDate::from_ymd_opt( 1, 1, 1 ).unwrap(), NaiveDate::from_ymd_opt( 9999, 12, 31 ).unwrap());
+        Dialog::show_payslips( &profile.name, &sqlite.payslips( &start, &end )? );
+        return Ok(());
+    }
+
+    let mut month = None;
+    let mut amounts = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.split_once('=') {
+            Some(("--month", m)) | Some(("-m", m)) => month = Period::parse( m ),
+            None if arg == "--month" || arg == "-m" => month = iter.next().and_then( |m| Period::parse( m ) ),
+            Some((name @ ("--gross" | "--net" | "--prime" | "--overtime"), v)) =>
+                amounts.push( (name, v.parse::<f64>().map_err( |_| usage() )?) ),
+            _ => return Err( usage() ),
+        }
+    }
+    let Some(month) = month.filter( |p| p.nb_months == 1 ) else {
+        return Err( usage() );
+    };
+    let mut p = sqlite.payslips( &month.start, &month.end() )?.into_iter().next().unwrap_or( Payslip::new( month.start ) );
+    for (name, v) in amounts {
+        match name {
+            "--gross" => p.gross = v,
+            "--net" => p.net = v,
+            "--prime" => p.prime = v,
+            _ => p.overtime = v,
+        }
+    }
+    sqlite.save_payslip( &p )?;
+    println!( "{}", msg!( "payslip.saved", p ) );
+    Ok(())
+}
+
+/// reconcile : rapproche les bulletins de paie d'une période des montants calculés à partir des
+/// données de chaque mois, et signale les mois où la prime ou les heures supplémentaires payées
+/// sont différentes
+/// - params :
+///     - profile -> le profil concerné
+///     - args -> les arguments de la commande (la période, l'année en cours par défaut)
+/// - return : l'erreur Parse si la période est invalide, Config si le taux horaire du profil
+///   n'est pas défini, sinon l'erreur de lecture
+fn reconcile(profile: &Profile, args: &[String]) -> Result<()> {
+    let today = Local::now().date_naive();
+    let period = match args.first() {
+        Some(p) => Period::parse( p ),
+        None => Period::year( today.year() ),
+    };
+    let Some(period) = period.filter( |_| args.len() <= 1 ) else {
+        return Err( Error::Parse( msg!( "usage.reconcile" ) ) );
+    };
+    if profile.wage <= 0.0 {
+        return Err( Error::Config( msg!( "pay.no_wage", profile.name ) ) );
+    }
+
+    let sqlite = CaDaoSqlite::new( DB_URL, profile.id );
+    let mut v = Vec::new();
+    for p in sqlite.payslips( &period.start, &period.end() )? {
+        let mut cmonth = CaMonth::new( p.month, profile, &[] );
+        cmonth.retrieve_datas( DB_URL.to_string() )?;
+        let end = p.month + chrono::Months::new( 1 );
+        v.push( Reconciliation{ pay: estimate_pay( &sqlite, profile, &p.month, &end, cmonth.prime() )?, payslip: p } );
+    }
+    Dialog::show_reconcile( &period, &profile.name, &v );
+    Ok(())
+}
+
+/// summary : affiche les totaux et les moyennes des 12 mois glissants se terminant par un mois,
+/// et ceux de l'année de ce mois, avec le nombre de mois au dessus du seuil de la prime
+/// - params :
+///     - profile -> le profil concerné
+///     - args -> les arguments de la commande (le dernier mois, mm/yyyy, le mois en cours par
+///       défaut)
+/// - return : l'erreur Parse si le mois est invalide, ou l'erreur de lecture
+fn summary(profile: &Profile, args: &[String]) -> Result<()> {
+    let today = Local::now().date_naive();
+    let month = match args.first() {
+        Some(m) => Period::parse( m ),
+        None => Period::month( today.year(), today.month() ),
+    };
+    let Some(month) = month.filter( |p| p.nb_months == 1 && args.len() <= 1 ) else {
+        return Err( Error::Parse( msg!( "usage.summary" ) ) );
+    };
+    let sqlite = CaDaoSqlite::new( DB_URL, profile.id );
+    let rolling = sqlite.summary( &summary::Summary::rolling( &month ), profile.threshold )?;
+    let year = sqlite.summary( &Period::year( month.start.year() ).unwrap(), profile.threshold )?;
+    Dialog::show_summary( profile, &[rolling, year] );
+    Ok(())
+}
+
+/// manage_profiles : affiche la liste des profils, ou crée / modifie un profil
+/// - params : args -> vide pour la liste, sinon le nom du profil suivi des options
+///   --threshold=seuil et --rate=taux (en %)
+/// - return : l'erreur Parse si les options sont invalides, ou l'erreur de lecture ou
+///   d'enregistrement
+fn manage_profiles(args: &[String]) -> Result<()> {
+    let sqlite = CaDaoSqlite::new( DB_URL, DEFAULT_PROFILE );
+    let Some(name) = args.first() else {
+        Dialog::show_profiles( &sqlite.profiles()? );
+        return Ok(());
+    };
+
+    let mut p = match sqlite.profile( Some(name) ) {
+        Ok(p) => p,
+        // un profil inconnu est créé
+        Err(rusqlite::Error::QueryReturnedNoRows) => Profile{ id: 0, name: name.to_string(), threshold: DEFAULT_THRESHOLD,
+            rate: DEFAULT_RATE, wage: 0.0, overtime: Bracket::parse_list( DEFAULT_OVERTIME ).unwrap() },
+        Err(e) => return Err( Error::Db( e ) ),
+    };
+    let usage = || Error::Parse( msg!( "usage.profile" ) );
+    let number = |v: &str| v.parse::<f64>().map_err( |_| usage() );
+    for arg in args[1..].iter() {
+        match arg.split_once('=') {
+            Some(("--threshold", v)) => p.threshold = number( v )?,
+            Some(("--rate", v)) => p.rate = number( v )? / 100.0,
+            Some(("--wage", v)) => p.wage = number( v )?,
+            Some(("--overtime", v)) => p.overtime = Bracket::parse_list( v ).ok_or_else( usage )?,
+            _ => return Err( usage() ),
+        }
     }
+    sqlite.save_profile( &p )?;
+    println!( "{}", msg!( "profile.saved", p ) );
+    Ok(())
 }
 
-/// extract_date : sert à trouver la chaine (dd/mm/yyyy) située dérière le signe '=' dans
+/// extract_date : sert à trouver la chaine (la date) située dérière le signe '=' dans
 /// l'argument
-/// - params : arg -> chaine représentant l'argument ( --month=dd/mm/yyyy ou -m=dd/mm/yyyy )
+/// - params : arg -> chaine représentant l'argument ( --month=date ou -m=date )
 /// - return : une sous chaine de arg
 fn extract_date( arg: &str ) -> &str {
     let octets = arg.as_bytes();
@@ -92,5 +749,5 @@ fn extract_date( arg: &str ) -> &str {
     for (i, &element) in octets.iter().enumerate() {
         if element == b'=' { let i = i + 1; return &arg[i..]; }
     }
-    return &arg[..];
+    arg
 }
@@ -0,0 +1,26 @@
+//! # markup :
+//! Ce module regroupe ce qui est commun aux documents HTML et XML produits par fde (pages web,
+//! relevé mensuel, graphiques SVG) : la protection des caractères spéciaux des textes insérés.
+//! - author : Thierry Probst <thierry.probst@free.fr>
+//! - version : 1.0.0
+//! - date : 19/10/2026
+
+
+/// escape : protège les caractères spéciaux HTML / XML d'un texte (& < > " et '), qui peut alors
+/// être placé dans un élément ou dans la valeur d'un attribut
+/// - params : s -> le texte
+/// - return : le texte protégé
+pub(crate) fn escape( s: &str ) -> String {
+    s.replace( '&', "&amp;" ).replace( '<', "&lt;" ).replace( '>', "&gt;" ).replace( '"', "&quot;" ).replace( '\'', "&#39;" )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn special_characters() {
+        assert_eq!( escape( "l'a<b> & \"c\"" ), "l&#39;a&lt;b&gt; &amp; &quot;c&quot;" );
+        assert_eq!( escape( "&lt;" ), "&amp;lt;" );
+    }
+}
@@ -0,0 +1,251 @@
+//! # pay :
+//! Ce module estime le salaire brut d'un mois pour le comparer au bulletin de paie : les heures
+//! normales au taux horaire de base, les heures supplémentaires majorées par tranches (+25 % pour
+//! les 8 premières heures de la semaine, +50 % au delà par défaut) et la prime du mois. Le taux
+//! horaire et les tranches de majoration sont des règles du profil (cf. fichier profile.rs).
+//! Les montants réellement payés, relevés sur les bulletins (table PAYSLIP), sont rapprochés de
+//! ceux calculés à partir des données du mois.
+//! - author : Thierry Probst <thierry.probst@free.fr>
+//! - version : 1.0.0
+//! - date : 19/10/2026
+use std::fmt;
+use chrono::{Datelike, Days, NaiveDate};
+use crate::i18n::{self, msg};
+use crate::period::PeriodTotal;
+use crate::profile::Profile;
+
+
+/// l'écart (en euros) en deçà duquel un montant payé est égal au montant calculé (arrondis)
+pub const TOLERANCE: f64 = 0.01;
+/// les tranches de majoration par défaut : 8 heures à +25 % par semaine, puis +50 %
+pub const DEFAULT_OVERTIME: &str = "8:25,50";
+
+/// une tranche de majoration des heures supplémentaires
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bracket {
+    /// le nombre d'heures supplémentaires de la semaine couvertes par la tranche (None pour toutes
+    /// les heures restantes)
+    pub hours: Option<f64>,
+    /// le taux de majoration (0.25 pour +25 %)
+    pub rate: f64,
+}
+
+impl Bracket {
+    /// parse_list : construit les tranches à partir d'une chaine de la forme "8:25,50" (8 heures
+    /// à +25 %, puis +50 %). Seule la dernière tranche n'a pas de nombre d'heures.
+    /// - params : s -> la chaine à analyser
+    /// - return : les tranches, None si le format n'est pas reconnu
+    pub fn parse_list( s: &str ) -> Option<Vec<Bracket>> {
+        let parts: Vec<&str> = s.split(',').map( |p| p.trim() ).collect();
+        let mut v = Vec::new();
+        for (i, p) in parts.iter().enumerate() {
+            let last = i == parts.len() - 1;
+            let b = match (p.split_once(':'), last) {
+                (Some((h, r)), false) => Bracket{ hours: Some( h.trim().parse().ok()? ), rate: r.trim().parse::<f64>().ok()? / 100.0 },
+                (None, true) => Bracket{ hours: None, rate: p.parse::<f64>().ok()? / 100.0 },
+                _ => return None,
+            };
+            if b.rate < 0.0 || b.hours.is_some_and( |h| h <= 0.0 ) { return None; }
+            v.push( b );
+        }
+        Some( v )
+    }
+
+    /// to_list : la chaine représentant des tranches, au format lu par parse_list
+    /// - params : v -> les tranches
+    /// - return : une chaine de la forme "8:25,50"
+    pub fn to_list( v: &[Bracket] ) -> String {
+        v.iter().map( |b| match b.hours {
+            Some(h) => format!( "{h}:{}", b.rate * 100.0 ),
+            None => format!( "{}", b.rate * 100.0 ),
+        }).collect::<Vec<String>>().join(",")
+    }
+
+    /// amount : le montant des heures supplémentaires payées dans cette tranche
+    /// - params : hours, wage -> le nombre d'heures et le taux horaire de base
+    /// - return : un float
+    pub fn amount( &self, hours: f64, wage: f64 ) -> f64 {
+        hours * wage * (1.0 + self.rate)
+    }
+}
+
+impl fmt::Display for Bracket {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "+{} %", i18n::decimal( self.rate * 100.0 ))
+    }
+}
+
+#[derive(Debug)]
+pub struct Pay {
+    /// le taux horaire de base
+    pub wage: f64,
+    /// le nombre d'heures normales (hors heures supplémentaires)
+    pub hours: f64,
+    /// le nombre d'heures supplémentaires de chaque tranche de majoration
+    pub overtime: Vec<(Bracket, f64)>,
+    /// la prime du mois
+    pub prime: f64,
+}
+
+impl Pay {
+    /// whole_weeks : les bornes des semaines entières (du lundi au dimanche) qui couvrent une
+    /// période, sur lesquelles les tranches de majoration sont calculées
+    /// - params : start, end -> le premier jour de la période et le premier jour qui la suit
+    /// - return : le lundi de la première semaine et le lundi qui suit la dernière
+    pub fn whole_weeks( start: &NaiveDate, end: &NaiveDate ) -> (NaiveDate, NaiveDate) {
+        (*start - Days::new( start.weekday().num_days_from_monday() as u64 ),
+            *end + Days::new( (7 - end.weekday().num_days_from_monday() as u64) % 7 ))
+    }
+
+    /// estimate : estime la paie d'un mois. Les heures supplémentaires sont réparties dans les
+    /// tranches de majoration sur chaque semaine entière (cf. whole_weeks), puis le mois reçoit
+    /// la part de chaque tranche correspondant à ses heures supplémentaires dans la semaine : une
+    /// semaine à cheval sur deux mois n'a ainsi qu'une fois ses premières heures majorées.
+    /// - params :
+    ///     - profile -> le profil concerné (taux horaire et tranches de majoration)
+    ///     - weeks -> les totaux de chacune des semaines du mois (limitées aux jours du mois)
+    ///     - whole -> les totaux des mêmes semaines entières
+    ///     - prime -> la prime du mois (cf. CaMonth::prime)
+    /// - return : un objet Pay
+    pub fn estimate( profile: &Profile, weeks: &[PeriodTotal], whole: &[PeriodTotal], prime: f64 ) -> Pay {
+        let mut overtime: Vec<(Bracket, f64)> = profile.overtime.iter().map( |b| (b.clone(), 0.0) ).collect();
+        for w in weeks.iter() {
+            let week = whole.iter().find( |t| t.start == w.start ).map_or( w.hsup, |t| t.hsup.max( w.hsup ) );
+            if week <= 0.0 { continue; }
+            let share = w.hsup / week;
+            let mut rest = week;
+            for (b, hours) in overtime.iter_mut() {
+                let h = b.hours.map_or( rest, |max| rest.min( max ) );
+                *hours += h * share;
+                rest -= h;
+            }
+        }
+        Pay{
+            wage: profile.wage,
+            hours: weeks.iter().map( |w| w.hours - w.hsup ).sum(),
+            overtime,
+            prime,
+        }
+    }
+
+    /// base : le salaire des heures normales
+    /// - params : self -> la strucure concernée
+    /// - return : un float
+    pub fn base(&self) -> f64 {
+        self.hours * self.wage
+    }
+
+    /// overtime_pay : le montant de toutes les heures supplémentaires
+    /// - params : self -> la strucure concernée
+    /// - return : un float
+    pub fn overtime_pay(&self) -> f64 {
+        self.overtime.iter().map( |(b, h)| b.amount( *h, self.wage ) ).sum()
+    }
+
+    /// gross : le salaire brut estimé (heures normales, heures supplémentaires et prime)
+    /// - params : self -> la strucure concernée
+    /// - return : un float
+    pub fn gross(&self) -> f64 {
+        self.base() + self.overtime_pay() + self.prime
+    }
+}
+
+/// les montants payés pour un mois, relevés sur le bulletin de paie
+#[derive(Debug, Clone)]
+pub struct Payslip {
+    /// le premier jour du mois payé
+    pub month: NaiveDate,
+    /// le salaire brut
+    pub gross: f64,
+    /// le salaire net
+    pub net: f64,
+    /// la prime payée
+    pub prime: f64,
+    /// le montant payé pour les heures supplémentaires
+    pub overtime: f64,
+}
+
+impl Payslip {
+    /// new : crée un bulletin vide (tous les montants à 0)
+    /// - params : month -> le premier jour du mois payé
+    /// - return : un objet Payslip
+    pub fn new( month: NaiveDate ) -> Payslip {
+        Payslip{ month, gross: 0.0, net: 0.0, prime: 0.0, overtime: 0.0 }
+    }
+}
+
+impl fmt::Display for Payslip {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", msg!( "payslip.line", i18n::month( &self.month ), i18n::number( self.gross, 2 ), i18n::number( self.net, 2 ),
+            i18n::number( self.prime, 2 ), i18n::number( self.overtime, 2 ) ))
+    }
+}
+
+/// le rapprochement d'un bulletin de paie avec la paie calculée pour le même mois
+#[derive(Debug)]
+pub struct Reconciliation {
+    /// les montants payés
+    pub payslip: Payslip,
+    /// la paie calculée à partir des données du mois
+    pub pay: Pay,
+}
+
+impl Reconciliation {
+    /// prime_gap : l'écart entre la prime payée et la prime calculée
+    /// - params : self -> la strucure concernée
+    /// - return : un float, positif si la prime payée est supérieure
+    pub fn prime_gap(&self) -> f64 {
+        self.payslip.prime - self.pay.prime
+    }
+
+    /// overtime_gap : l'écart entre les heures supplémentaires payées et celles calculées
+    /// - params : self -> la strucure concernée
+    /// - return : un float, positif si le montant payé est supérieur
+    pub fn overtime_gap(&self) -> f64 {
+        self.payslip.overtime - self.pay.overtime_pay()
+    }
+
+    /// flags : les écarts à signaler (supérieurs à TOLERANCE)
+    /// - params : self -> la strucure concernée
+    /// - return : un Vecteur contenant la description de chaque écart (vide si le bulletin est
+    ///   conforme)
+    pub fn flags(&self) -> Vec<String> {
+        let mut v = Vec::new();
+        if self.prime_gap().abs() > TOLERANCE {
+            v.push( msg!( "reconcile.prime", i18n::number( self.payslip.prime, 2 ), i18n::number( self.pay.prime, 2 ),
+                i18n::signed( self.prime_gap(), 2 ) ) );
+        }
+        if self.overtime_gap().abs() > TOLERANCE {
+            v.push( msg!( "reconcile.overtime", i18n::number( self.payslip.overtime, 2 ), i18n::number( self.pay.overtime_pay(), 2 ),
+                i18n::signed( self.overtime_gap(), 2 ) ) );
+        }
+        v
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn week( start: NaiveDate, hours: f64, hsup: f64 ) -> PeriodTotal {
+        PeriodTotal{ start, ca: 0.0, hours, hsup, days: 1 }
+    }
+
+    #[test]
+    fn week_across_two_months() {
+        let profile = Profile{ id: 1, name: "Nat".to_string(), threshold: 0.0, rate: 0.0, wage: 10.0,
+            overtime: Bracket::parse_list( DEFAULT_OVERTIME ).unwrap() };
+        // la semaine du lundi 27/05/2024 : 6 h sup en mai (27 au 31) et 6 h sup en juin (1er et 2)
+        let monday = NaiveDate::from_ymd_opt( 2024, 5, 27 ).unwrap();
+        assert_eq!( Pay::whole_weeks( &NaiveDate::from_ymd_opt( 2024, 6, 1 ).unwrap(), &NaiveDate::from_ymd_opt( 2024, 7, 1 ).unwrap() ),
+            (monday, NaiveDate::from_ymd_opt( 2024, 7, 1 ).unwrap()) );
+        let whole = [ week( monday, 52.0, 12.0 ) ];
+        let may = Pay::estimate( &profile, &[ week( monday, 41.0, 6.0 ) ], &whole, 0.0 );
+        let june = Pay::estimate( &profile, &[ week( monday, 11.0, 6.0 ) ], &whole, 0.0 );
+        // 8 h à +25 % et 4 h à +50 % sur la semaine, partagées à moitié entre les deux mois
+        assert_eq!( may.overtime[0].1 + june.overtime[0].1, 8.0 );
+        assert_eq!( may.overtime[1].1 + june.overtime[1].1, 4.0 );
+        assert_eq!( may.overtime[0].1, 4.0 );
+        assert!( (may.overtime_pay() + june.overtime_pay() - (8.0 * 12.5 + 4.0 * 15.0)).abs() < 1e-9 );
+    }
+}
@@ -0,0 +1,204 @@
+//! # period :
+//! Ce module définit une structure Period représentant une période composée de mois entiers :
+//! un mois, un trimestre ou une année.
+//! - author : Thierry Probst <thierry.probst@free.fr>
+//! - version : 1.0.0
+//! - date : 19/10/2026
+use std::fmt;
+use chrono::{Datelike, Days, Months, NaiveDate};
+use crate::dateexpr;
+use crate::i18n::{self, msg};
+
+
+#[derive(Debug, Clone)]
+pub struct Period {
+    /// le premier jour de la période
+    pub start: NaiveDate,
+    /// le nombre de mois couverts par la période
+    pub nb_months: u32,
+}
+
+/// le pas utilisé pour regrouper les jours d'une période
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Step {
+    Day,
+    /// une semaine, du lundi au dimanche
+    Week,
+    Month,
+}
+
+/// les totaux d'une journée, d'une semaine ou d'un mois
+#[derive(Debug, Clone, Default)]
+pub struct PeriodTotal {
+    /// le premier jour regroupé (le lundi pour une semaine, le 1er pour un mois)
+    pub start: NaiveDate,
+    /// le chiffre d'affaire
+    pub ca: f64,
+    /// le nombre d'heures
+    pub hours: f64,
+    /// le nombre d'heures supplémentaires
+    pub hsup: f64,
+    /// le nombre de jours saisis
+    pub days: usize,
+}
+
+impl Period {
+    /// parse : construit une période à partir d'une chaine. Les formats acceptés sont :
+    /// - mm/yyyy -> un mois
+    /// - Tn/yyyy ou Qn/yyyy -> un trimestre (n entre 1 et 4)
+    /// - yyyy -> une année
+    /// - une date (cf. dateexpr::parse) -> le mois contenant cette date
+    /// - params : s -> la chaine à analyser
+    /// - return : la période trouvée, None si le format n'est pas reconnu
+    pub fn parse( s: &str ) -> Option<Period> {
+        let s = s.trim();
+        let parts: Vec<&str> = s.split('/').collect();
+        let period = match parts.as_slice() {
+            [y] if y.len() == 4 => y.parse().ok().and_then( Period::year ),
+            [q, y] if q.starts_with(['T', 't', 'Q', 'q']) =>
+                y.parse().ok().zip( q[1..].parse().ok() ).and_then( |(y, q)| Period::quarter( y, q ) ),
+            [m, y] if y.len() == 4 => y.parse().ok().zip( m.parse().ok() ).and_then( |(y, m)| Period::month( y, m ) ),
+            _ => None,
+        };
+        period.or_else( || dateexpr::parse( s ).and_then( |d| Period::month( d.year(), d.month() ) ) )
+    }
+
+    /// month : construit la période correspondant à un mois
+    /// - params : year, month -> l'année et le mois (1 à 12)
+    /// - return : la période, None si le mois est invalide
+    pub fn month( year: i32, month: u32 ) -> Option<Period> {
+        Some( Period{ start: NaiveDate::from_ymd_opt( year, month, 1 )?, nb_months: 1 } )
+    }
+
+    /// quarter : construit la période correspondant à un trimestre
+    /// - params : year, quarter -> l'année et le trimestre (1 à 4)
+    /// - return : la période, None si le trimestre est invalide
+    pub fn quarter( year: i32, quarter: u32 ) -> Option<Period> {
+        if !(1..=4).contains( &quarter ) { return None; }
+        Some( Period{ start: NaiveDate::from_ymd_opt( year, quarter * 3 - 2, 1 )?, nb_months: 3 } )
+    }
+
+    /// year : construit la période correspondant à une année
+    /// - params : year -> l'année
+    /// - return : la période, None si l'année est invalide
+    pub fn year( year: i32 ) -> Option<Period> {
+        Some( Period{ start: NaiveDate::from_ymd_opt( year, 1, 1 )?, nb_months: 12 } )
+    }
+
+    /// end : le premier jour suivant la période (borne exclue)
+    /// - params : aucun
+    /// - return : une date
+    pub fn end( &self ) -> NaiveDate {
+        self.start + Months::new( self.nb_months )
+    }
+
+    /// months : la liste des mois de la période
+    /// - params : aucun
+    /// - return : un Vecteur contenant le premier jour de chacun des mois
+    pub fn months( &self ) -> Vec<NaiveDate> {
+        (0..self.nb_months).map( |i| self.start + Months::new( i ) ).collect()
+    }
+}
+
+impl Step {
+    /// first : le premier jour du regroupement contenant une date
+    /// - params : d -> la date
+    /// - return : la date elle même, le lundi de sa semaine ou le 1er de son mois
+    pub fn first( &self, d: NaiveDate ) -> NaiveDate {
+        match self {
+            Step::Day => d,
+            Step::Week => d - Days::new( d.weekday().num_days_from_monday() as u64 ),
+            Step::Month => d.with_day( 1 ).unwrap(),
+        }
+    }
+
+    /// next : le premier jour du regroupement suivant
+    /// - params : d -> le premier jour d'un regroupement
+    /// - return : une date
+    pub fn next( &self, d: NaiveDate ) -> NaiveDate {
+        match self {
+            Step::Day => d + Days::new( 1 ),
+            Step::Week => d + Days::new( 7 ),
+            Step::Month => d + Months::new( 1 ),
+        }
+    }
+
+    /// fill : complète des totaux avec les regroupements sans données (totaux à 0)
+    /// - params :
+    ///     - start, end -> le premier jour de la période et le premier jour qui la suit
+    ///     - totals -> les totaux trouvés, triés par date
+    /// - return : un Vecteur contenant un total par regroupement de la période
+    pub fn fill( &self, start: NaiveDate, end: NaiveDate, totals: &[PeriodTotal] ) -> Vec<PeriodTotal> {
+        let mut v = Vec::new();
+        let mut d = self.first( start );
+        while d < end {
+            v.push( totals.iter().find( |t| t.start == d ).cloned()
+                .unwrap_or( PeriodTotal{ start: d, ..Default::default() } ) );
+            d = self.next( d );
+        }
+        v
+    }
+}
+
+impl fmt::Display for Period {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.nb_months {
+            1 => write!(f, "{}", i18n::month( &self.start )),
+            3 => write!(f, "{}", msg!( "period.quarter", self.start.month0() / 3 + 1, self.start.year() )),
+            12 if self.start.month() == 1 => write!(f, "{}", self.start.year()),
+            _ => write!(f, "{} - {}", i18n::month( &self.start ), i18n::month( &self.end().pred_opt().unwrap() )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt( y, m, d ).unwrap()
+    }
+
+    /// parsed : le premier jour et le nombre de mois de la période analysée
+    fn parsed(s: &str) -> Option<(NaiveDate, u32)> {
+        Period::parse( s ).map( |p| (p.start, p.nb_months) )
+    }
+
+    #[test]
+    fn months_quarters_years() {
+        assert_eq!( parsed( "05/2024" ), Some( (date( 2024, 5, 1 ), 1) ) );
+        assert_eq!( parsed( "5/2024" ), Some( (date( 2024, 5, 1 ), 1) ) );
+        assert_eq!( parsed( "T2/2024" ), Some( (date( 2024, 4, 1 ), 3) ) );
+        assert_eq!( parsed( "q4/2023" ), Some( (date( 2023, 10, 1 ), 3) ) );
+        assert_eq!( parsed( "2024" ), Some( (date( 2024, 1, 1 ), 12) ) );
+    }
+
+    #[test]
+    fn dates_give_their_month() {
+        assert_eq!( parsed( "15/05/2024" ), Some( (date( 2024, 5, 1 ), 1) ) );
+        assert_eq!( parsed( "2024-02-29" ), Some( (date( 2024, 2, 1 ), 1) ) );
+        let today = dateexpr::today();
+        assert_eq!( parsed( "today" ), Some( (today.with_day( 1 ).unwrap(), 1) ) );
+    }
+
+    #[test]
+    fn invalid() {
+        for s in ["", "13/2024", "T5/2024", "T0/2024", "24", "mai", "00/2024"] {
+            assert!( Period::parse( s ).is_none(), "{s}" );
+        }
+    }
+
+    #[test]
+    fn end_and_months() {
+        let q = Period::quarter( 2024, 4 ).unwrap();
+        assert_eq!( q.end(), date( 2025, 1, 1 ) );
+        assert_eq!( q.months(), vec![ date( 2024, 10, 1 ), date( 2024, 11, 1 ), date( 2024, 12, 1 ) ] );
+    }
+
+    #[test]
+    fn weeks_start_on_monday() {
+        assert_eq!( Step::Week.first( date( 2024, 5, 15 ) ), date( 2024, 5, 13 ) );
+        assert_eq!( Step::Week.next( date( 2024, 5, 13 ) ), date( 2024, 5, 20 ) );
+        assert_eq!( Step::Month.first( date( 2024, 5, 15 ) ), date( 2024, 5, 1 ) );
+    }
+}
@@ -0,0 +1,100 @@
+//! # profile :
+//! Ce module définit une structure Profile représentant une personne dont l'activité est suivie,
+//! ainsi que ses règles de calcul de la prime et de la paie. Cela correspond à un enregistrement de la table
+//! PROFILE du fichier Sqlite3
+//! - author : Thierry Probst <thierry.probst@free.fr>
+//! - version : 1.0.0
+//! - date : 19/10/2026
+use std::fmt;
+use crate::i18n::{self, msg};
+use crate::pay::Bracket;
+
+
+/// l'identifiant du profil par défaut, auquel appartiennent les données antérieures aux profils
+pub const DEFAULT_PROFILE: i64 = 1;
+/// le seuil de chiffre d'affaire mensuel par défaut au delà duquel une prime est versée
+pub const DEFAULT_THRESHOLD: f64 = 3421.15;
+/// le taux de la prime par défaut, appliqué au chiffre d'affaire du mois
+pub const DEFAULT_RATE: f64 = 0.02;
+
+#[derive(Debug, Clone)]
+pub struct Profile {
+    /// l'identifiant du profil dans la table PROFILE
+    pub id: i64,
+    /// le nom de la personne
+    pub name: String,
+    /// le seuil de chiffre d'affaire mensuel au delà duquel une prime est versée
+    pub threshold: f64,
+    /// le taux de la prime appliqué au chiffre d'affaire du mois
+    pub rate: f64,
+    /// le taux horaire brut de base (0 s'il n'est pas renseigné)
+    pub wage: f64,
+    /// les tranches de majoration des heures supplémentaires de la semaine
+    pub overtime: Vec<Bracket>,
+}
+
+impl Profile {
+    /// delta : l'écart entre un chiffre d'affaire mensuel et le seuil de la prime
+    /// - params : ca -> le chiffre d'affaire du mois
+    /// - return : un float, positif si le seuil est dépassé
+    pub fn delta(&self, ca: f64) -> f64 {
+        ca - self.threshold
+    }
+
+    /// prime : le montant de la prime due pour un chiffre d'affaire mensuel
+    /// - params : ca -> le chiffre d'affaire du mois
+    /// - return : un float, nul si le seuil n'est pas dépassé
+    pub fn prime(&self, ca: f64) -> f64 {
+        if self.delta( ca ) > 0.0 { ca * self.rate } else { 0.0 }
+    }
+
+    /// selector : récupère le nom du profil indiqué par l'option --profile=nom
+    /// - params : args -> les arguments de la ligne de commande
+    /// - return : le nom du profil, None si l'option est absente
+    pub fn selector( args: &[String] ) -> Option<String> {
+        args.iter().find_map( |a| a.strip_prefix("--profile=") ).map( |s| s.to_string() )
+    }
+}
+
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", msg!( "profile.line", self.name, i18n::number( self.threshold, 2 ), i18n::number( self.rate * 100.0, 2 ),
+            i18n::number( self.wage, 2 ), self.overtime.iter().map( |b| match b.hours {
+                Some(h) => msg!( "profile.bracket", i18n::decimal( h ), b ),
+                None => b.to_string(),
+            }).collect::<Vec<String>>().join( &msg!( "profile.then" ) ) ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn nat() -> Profile {
+        Profile{ id: DEFAULT_PROFILE, name: "Nat".to_string(), threshold: DEFAULT_THRESHOLD, rate: DEFAULT_RATE, wage: 0.0,
+            overtime: Vec::new() }
+    }
+
+    #[test]
+    fn prime_above_threshold() {
+        // la prime porte sur tout le chiffre d'affaire du mois, pas seulement sur le dépassement
+        let p = nat();
+        assert!( (p.prime( 5000.0 ) - 100.0).abs() < 1e-9 );
+        assert!( (p.delta( 5000.0 ) - 1578.85).abs() < 1e-9 );
+    }
+
+    #[test]
+    fn no_prime_up_to_threshold() {
+        let p = nat();
+        assert_eq!( p.prime( 0.0 ), 0.0 );
+        assert_eq!( p.prime( DEFAULT_THRESHOLD ), 0.0 );
+        assert!( p.delta( 3000.0 ) < 0.0 );
+    }
+
+    #[test]
+    fn selector() {
+        let args: Vec<String> = ["fde", "--profile=Léa", "-m"].iter().map( |s| s.to_string() ).collect();
+        assert_eq!( Profile::selector( &args ).as_deref(), Some( "Léa" ) );
+        assert_eq!( Profile::selector( &args[2..] ), None );
+    }
+}
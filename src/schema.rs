@@ -0,0 +1,302 @@
+//! # schema :
+//! Ce module gère l'évolution du schéma du fichier sqlite3. Chaque migration est un script SQL
+//! appliqué une seule fois, dans l'ordre. Le numéro de la dernière migration appliquée est
+//! conservé dans `PRAGMA user_version`.
+//! - author : Thierry Probst <thierry.probst@free.fr>
+//! - version : 1.0.0
+//! - date : 19/10/2026
+use rusqlite::{Connection, Result};
+use crate::backup;
+use crate::i18n::msg;
+
+
+/// les tables (ou vues) qui doivent exister à partir d'une version du schéma, la table CA de
+/// l'application python existe avant toute migration
+const REQUIRED: &[(usize, &str)] = &[ (0, "CA"), (2, "CA_FTS"), (3, "CA_TAG"), (4, "ENTRY"), (5, "PROFILE"),
+    (6, "HISTORY"), (7, "SETTING"), (9, "PAYSLIP") ];
+
+/// la liste ordonnée des migrations, la migration i amène le schéma à la version i + 1
+const MIGRATIONS: &[&str] = &[
+    // 1 : la table CA telle qu'elle est créée par l'application python
+    "CREATE TABLE IF NOT EXISTS CA (
+        date    TEXT NOT NULL UNIQUE,
+        ca      INTEGER,
+        hours   REAL,
+        hsup    REAL NOT NULL DEFAULT 0,
+        comment TEXT,
+        PRIMARY KEY(date)
+    );",
+    // 2 : index plein texte sur les commentaires, maintenu par des triggers
+    "CREATE VIRTUAL TABLE CA_FTS USING fts5(
+        date UNINDEXED, comment, tokenize = 'unicode61 remove_diacritics 2'
+    );
+    INSERT INTO CA_FTS (date, comment) SELECT date, comment FROM CA WHERE comment IS NOT NULL;
+    CREATE TRIGGER ca_fts_insert AFTER INSERT ON CA BEGIN
+        INSERT INTO CA_FTS (date, comment) VALUES (new.date, new.comment);
+    END;
+    CREATE TRIGGER ca_fts_delete AFTER DELETE ON CA BEGIN
+        DELETE FROM CA_FTS WHERE date = old.date;
+    END;
+    CREATE TRIGGER ca_fts_update AFTER UPDATE OF date, comment ON CA BEGIN
+        DELETE FROM CA_FTS WHERE date = old.date;
+        INSERT INTO CA_FTS (date, comment) VALUES (new.date, new.comment);
+    END;",
+    // 3 : les tags associés à chaque jour
+    "CREATE TABLE CA_TAG (
+        date    TEXT NOT NULL,
+        tag     TEXT NOT NULL,
+        PRIMARY KEY(date, tag)
+    );
+    CREATE TRIGGER ca_tag_delete AFTER DELETE ON CA BEGIN
+        DELETE FROM CA_TAG WHERE date = old.date;
+    END;",
+    // 4 : une journée peut contenir plusieurs entrées (activités). Les données sont déplacées
+    // dans la table ENTRY et CA devient une vue donnant le total de chaque journée. Les
+    // triggers INSTEAD OF permettent à l'application python de continuer à écrire dans CA :
+    // une modification regroupe les entrées de la journée en une seule.
+    "CREATE TABLE ENTRY (
+        id       INTEGER PRIMARY KEY,
+        date     TEXT NOT NULL,
+        activity TEXT NOT NULL DEFAULT '',
+        ca       INTEGER,
+        hours    REAL,
+        hsup     REAL NOT NULL DEFAULT 0,
+        comment  TEXT
+    );
+    CREATE INDEX entry_date ON ENTRY (date);
+    INSERT INTO ENTRY (date, ca, hours, hsup, comment)
+        SELECT date, ca, hours, hsup, comment FROM CA ORDER BY date;
+    DROP TABLE CA;
+    CREATE VIEW CA AS
+        SELECT date, SUM(ca) AS ca, SUM(hours) AS hours, SUM(hsup) AS hsup,
+            group_concat(comment, ' / ') AS comment
+        FROM (SELECT * FROM ENTRY ORDER BY date, id) GROUP BY date;
+    CREATE TRIGGER ca_insert INSTEAD OF INSERT ON CA BEGIN
+        INSERT INTO ENTRY (date, ca, hours, hsup, comment)
+            VALUES (new.date, new.ca, new.hours, COALESCE(new.hsup, 0), new.comment);
+    END;
+    CREATE TRIGGER ca_update INSTEAD OF UPDATE ON CA BEGIN
+        DELETE FROM ENTRY WHERE date = old.date
+            AND id <> (SELECT MIN(id) FROM ENTRY WHERE date = old.date);
+        UPDATE ENTRY SET date = new.date, ca = new.ca, hours = new.hours,
+            hsup = COALESCE(new.hsup, 0), comment = new.comment
+            WHERE date = old.date;
+    END;
+    CREATE TRIGGER ca_delete INSTEAD OF DELETE ON CA BEGIN
+        DELETE FROM ENTRY WHERE date = old.date;
+    END;
+    DROP TABLE CA_FTS;
+    CREATE VIRTUAL TABLE CA_FTS USING fts5(
+        date UNINDEXED, comment, tokenize = 'unicode61 remove_diacritics 2'
+    );
+    INSERT INTO CA_FTS (rowid, date, comment) SELECT id, date, comment FROM ENTRY WHERE comment IS NOT NULL;
+    CREATE TRIGGER entry_fts_insert AFTER INSERT ON ENTRY BEGIN
+        INSERT INTO CA_FTS (rowid, date, comment) VALUES (new.id, new.date, new.comment);
+    END;
+    CREATE TRIGGER entry_fts_delete AFTER DELETE ON ENTRY BEGIN
+        DELETE FROM CA_FTS WHERE rowid = old.id;
+    END;
+    CREATE TRIGGER entry_fts_update AFTER UPDATE OF date, comment ON ENTRY BEGIN
+        DELETE FROM CA_FTS WHERE rowid = old.id;
+        INSERT INTO CA_FTS (rowid, date, comment) VALUES (new.id, new.date, new.comment);
+    END;
+    CREATE TRIGGER entry_tag_delete AFTER DELETE ON ENTRY
+        WHEN NOT EXISTS (SELECT 1 FROM ENTRY WHERE date = old.date) BEGIN
+        DELETE FROM CA_TAG WHERE date = old.date;
+    END;",
+    // 5 : plusieurs personnes (profils) dans le même fichier. Les données existantes sont
+    // rattachées au profil par défaut (1). La vue DAY donne le total de chaque journée de chaque
+    // profil, la vue CA reste celle de l'application python et ne contient que le profil 1.
+    "CREATE TABLE PROFILE (
+        id        INTEGER PRIMARY KEY,
+        name      TEXT NOT NULL UNIQUE,
+        threshold REAL NOT NULL DEFAULT 3421.15,
+        rate      REAL NOT NULL DEFAULT 0.02
+    );
+    INSERT INTO PROFILE (id, name) VALUES (1, 'Nat');
+    ALTER TABLE ENTRY ADD COLUMN profile_id INTEGER NOT NULL DEFAULT 1;
+    DROP INDEX entry_date;
+    CREATE INDEX entry_profile_date ON ENTRY (profile_id, date);
+    DROP TRIGGER entry_tag_delete;
+    CREATE TABLE CA_TAG_NEW (
+        profile_id INTEGER NOT NULL DEFAULT 1,
+        date       TEXT NOT NULL,
+        tag        TEXT NOT NULL,
+        PRIMARY KEY(profile_id, date, tag)
+    );
+    INSERT INTO CA_TAG_NEW (date, tag) SELECT date, tag FROM CA_TAG;
+    DROP TABLE CA_TAG;
+    ALTER TABLE CA_TAG_NEW RENAME TO CA_TAG;
+    CREATE TRIGGER entry_tag_delete AFTER DELETE ON ENTRY
+        WHEN NOT EXISTS (SELECT 1 FROM ENTRY WHERE profile_id = old.profile_id AND date = old.date) BEGIN
+        DELETE FROM CA_TAG WHERE profile_id = old.profile_id AND date = old.date;
+    END;
+    CREATE VIEW DAY AS
+        SELECT profile_id, date, SUM(ca) AS ca, SUM(hours) AS hours, SUM(hsup) AS hsup,
+            group_concat(comment, ' / ') AS comment
+        FROM (SELECT * FROM ENTRY ORDER BY profile_id, date, id) GROUP BY profile_id, date;
+    DROP VIEW CA;
+    CREATE VIEW CA AS SELECT date, ca, hours, hsup, comment FROM DAY WHERE profile_id = 1;
+    CREATE TRIGGER ca_insert INSTEAD OF INSERT ON CA BEGIN
+        INSERT INTO ENTRY (profile_id, date, ca, hours, hsup, comment)
+            VALUES (1, new.date, new.ca, new.hours, COALESCE(new.hsup, 0), new.comment);
+    END;
+    CREATE TRIGGER ca_update INSTEAD OF UPDATE ON CA BEGIN
+        DELETE FROM ENTRY WHERE profile_id = 1 AND date = old.date
+            AND id <> (SELECT MIN(id) FROM ENTRY WHERE profile_id = 1 AND date = old.date);
+        UPDATE ENTRY SET date = new.date, ca = new.ca, hours = new.hours,
+            hsup = COALESCE(new.hsup, 0), comment = new.comment
+            WHERE profile_id = 1 AND date = old.date;
+    END;
+    CREATE TRIGGER ca_delete INSTEAD OF DELETE ON CA BEGIN
+        DELETE FROM ENTRY WHERE profile_id = 1 AND date = old.date;
+    END;",
+    // 6 : l'historique des modifications des entrées. La source vaut 'externe' par défaut, fde
+    // la remplace par la sienne dans la transaction qui a provoqué la modification.
+    "CREATE TABLE HISTORY (
+        id           INTEGER PRIMARY KEY,
+        ts           TEXT NOT NULL DEFAULT (strftime('%Y-%m-%d %H:%M:%S', 'now', 'localtime')),
+        op           TEXT NOT NULL,
+        source       TEXT NOT NULL DEFAULT 'externe',
+        entry_id     INTEGER NOT NULL,
+        profile_id   INTEGER NOT NULL,
+        date         TEXT NOT NULL,
+        old_activity TEXT, old_ca INTEGER, old_hours REAL, old_hsup REAL, old_comment TEXT,
+        new_activity TEXT, new_ca INTEGER, new_hours REAL, new_hsup REAL, new_comment TEXT
+    );
+    CREATE INDEX history_profile_date ON HISTORY (profile_id, date);
+    CREATE TRIGGER entry_history_insert AFTER INSERT ON ENTRY BEGIN
+        INSERT INTO HISTORY (op, entry_id, profile_id, date,
+            new_activity, new_ca, new_hours, new_hsup, new_comment)
+        VALUES ('add', new.id, new.profile_id, new.date,
+            new.activity, new.ca, new.hours, new.hsup, new.comment);
+    END;
+    CREATE TRIGGER entry_history_update AFTER UPDATE ON ENTRY
+        WHEN old.date IS NOT new.date OR old.activity IS NOT new.activity OR old.ca IS NOT new.ca
+        OR old.hours IS NOT new.hours OR old.hsup IS NOT new.hsup OR old.comment IS NOT new.comment BEGIN
+        INSERT INTO HISTORY (op, entry_id, profile_id, date,
+            old_activity, old_ca, old_hours, old_hsup, old_comment,
+            new_activity, new_ca, new_hours, new_hsup, new_comment)
+        VALUES ('update', new.id, new.profile_id, new.date,
+            old.activity, old.ca, old.hours, old.hsup, old.comment,
+            new.activity, new.ca, new.hours, new.hsup, new.comment);
+    END;
+    CREATE TRIGGER entry_history_delete AFTER DELETE ON ENTRY BEGIN
+        INSERT INTO HISTORY (op, entry_id, profile_id, date,
+            old_activity, old_ca, old_hours, old_hsup, old_comment)
+        VALUES ('delete', old.id, old.profile_id, old.date,
+            old.activity, old.ca, old.hours, old.hsup, old.comment);
+    END;",
+    // 7 : suppression logique (corbeille). Une entrée supprimée garde ses valeurs avec la date de
+    // sa suppression dans deleted_at et n'apparait plus dans les vues. Les suppressions de
+    // l'application python passent aussi par la corbeille. La table SETTING contient les
+    // paramètres de fde, dont la durée de conservation (en jours) des entrées de la corbeille.
+    "ALTER TABLE ENTRY ADD COLUMN deleted_at TEXT;
+    CREATE TABLE SETTING (
+        key   TEXT NOT NULL PRIMARY KEY,
+        value TEXT
+    );
+    INSERT INTO SETTING (key, value) VALUES ('trash_days', '30');
+    DROP VIEW CA;
+    DROP VIEW DAY;
+    CREATE VIEW DAY AS
+        SELECT profile_id, date, SUM(ca) AS ca, SUM(hours) AS hours, SUM(hsup) AS hsup,
+            group_concat(comment, ' / ') AS comment
+        FROM (SELECT * FROM ENTRY WHERE deleted_at IS NULL ORDER BY profile_id, date, id)
+        GROUP BY profile_id, date;
+    CREATE VIEW CA AS SELECT date, ca, hours, hsup, comment FROM DAY WHERE profile_id = 1;
+    CREATE TRIGGER ca_insert INSTEAD OF INSERT ON CA BEGIN
+        INSERT INTO ENTRY (profile_id, date, ca, hours, hsup, comment)
+            VALUES (1, new.date, new.ca, new.hours, COALESCE(new.hsup, 0), new.comment);
+    END;
+    CREATE TRIGGER ca_update INSTEAD OF UPDATE ON CA BEGIN
+        DELETE FROM ENTRY WHERE profile_id = 1 AND date = old.date AND deleted_at IS NULL
+            AND id <> (SELECT MIN(id) FROM ENTRY WHERE profile_id = 1 AND date = old.date AND deleted_at IS NULL);
+        UPDATE ENTRY SET date = new.date, ca = new.ca, hours = new.hours,
+            hsup = COALESCE(new.hsup, 0), comment = new.comment
+            WHERE profile_id = 1 AND date = old.date AND deleted_at IS NULL;
+    END;
+    CREATE TRIGGER ca_delete INSTEAD OF DELETE ON CA BEGIN
+        UPDATE ENTRY SET deleted_at = strftime('%Y-%m-%d %H:%M:%S', 'now', 'localtime')
+            WHERE profile_id = 1 AND date = old.date AND deleted_at IS NULL;
+    END;
+    DROP TRIGGER entry_history_update;
+    CREATE TRIGGER entry_history_update AFTER UPDATE ON ENTRY
+        WHEN old.date IS NOT new.date OR old.activity IS NOT new.activity OR old.ca IS NOT new.ca
+        OR old.hours IS NOT new.hours OR old.hsup IS NOT new.hsup OR old.comment IS NOT new.comment
+        OR old.deleted_at IS NOT new.deleted_at BEGIN
+        INSERT INTO HISTORY (op, entry_id, profile_id, date,
+            old_activity, old_ca, old_hours, old_hsup, old_comment,
+            new_activity, new_ca, new_hours, new_hsup, new_comment)
+        VALUES (CASE
+                WHEN old.deleted_at IS NULL AND new.deleted_at IS NOT NULL THEN 'trash'
+                WHEN old.deleted_at IS NOT NULL AND new.deleted_at IS NULL THEN 'untrash'
+                ELSE 'update' END,
+            new.id, new.profile_id, new.date,
+            old.activity, old.ca, old.hours, old.hsup, old.comment,
+            new.activity, new.ca, new.hours, new.hsup, new.comment);
+    END;",
+    // 8 : les règles de la paie de chaque profil : le taux horaire brut de base et les tranches de
+    // majoration des heures supplémentaires de la semaine (cf. fichier pay.rs)
+    "ALTER TABLE PROFILE ADD COLUMN wage REAL NOT NULL DEFAULT 0;
+    ALTER TABLE PROFILE ADD COLUMN overtime TEXT NOT NULL DEFAULT '8:25,50';",
+    // 9 : les montants réellement payés chaque mois, relevés sur les bulletins de paie (le mois
+    // est au format yyyy-mm)
+    "CREATE TABLE PAYSLIP (
+        profile_id INTEGER NOT NULL DEFAULT 1,
+        month      TEXT NOT NULL,
+        gross      REAL NOT NULL DEFAULT 0,
+        net        REAL NOT NULL DEFAULT 0,
+        prime      REAL NOT NULL DEFAULT 0,
+        overtime   REAL NOT NULL DEFAULT 0,
+        PRIMARY KEY(profile_id, month)
+    );",
+];
+
+/// migrate : applique les migrations qui ne l'ont pas encore été. Chaque migration est
+/// exécutée dans sa propre transaction. Un fichier qui contient déjà des données est sauvegardé
+/// avant (cf. fichier backup.rs).
+/// - params : conn -> une connexion ouverte sur le fichier sqlite3
+/// - return : un objet () en cas de succés sinon l'erreur Sqlite3
+pub fn migrate(conn: &mut Connection) -> Result<()> {
+    let version: usize = conn.query_row( "PRAGMA user_version", [], |row| row.get(0) )?;
+    if version >= MIGRATIONS.len() { return Ok(()); }
+
+    let nb_tables: usize = conn.query_row( "SELECT COUNT(*) FROM sqlite_master", [], |row| row.get(0) )?;
+    if nb_tables > 0 && !conn.path().unwrap_or_default().is_empty() {
+        backup::backup( conn, "migration" )?;
+    }
+
+    for (i, sql) in MIGRATIONS.iter().enumerate().skip( version ) {
+        let tx = conn.transaction()?;
+        tx.execute_batch( sql )?;
+        tx.pragma_update( None, "user_version", i + 1 )?;
+        tx.commit()?;
+    }
+    Ok(())
+}
+
+/// check : vérifie qu'un fichier sqlite3 peut être utilisé par fde : il doit être intègre, son
+/// schéma ne doit pas être plus récent que celui de fde et il doit contenir les tables de sa
+/// version.
+/// - params : conn -> une connexion ouverte sur le fichier sqlite3
+/// - return : la liste des problèmes trouvés (vide si le fichier est utilisable) sinon l'erreur
+///   Sqlite3 (NotADatabase si ce n'est pas un fichier sqlite3)
+pub fn check(conn: &Connection) -> Result<Vec<String>> {
+    let mut problems = Vec::new();
+    let integrity: String = conn.query_row( "PRAGMA integrity_check", [], |row| row.get(0) )?;
+    if integrity != "ok" {
+        problems.push( msg!( "schema.corrupted", integrity ) );
+    }
+    let version: usize = conn.query_row( "PRAGMA user_version", [], |row| row.get(0) )?;
+    if version > MIGRATIONS.len() {
+        problems.push( msg!( "schema.newer", version, MIGRATIONS.len() ) );
+    }
+    for (since, name) in REQUIRED.iter().filter( |(since, _)| *since <= version ) {
+        let nb: usize = conn.query_row( "SELECT COUNT(*) FROM sqlite_master WHERE name = ?1", [name], |row| row.get(0) )?;
+        if nb == 0 {
+            problems.push( msg!( "schema.missing", name, version, since ) );
+        }
+    }
+    Ok(problems)
+}
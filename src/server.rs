@@ -0,0 +1,525 @@
+//! # server :
+//! Ce module définit un Server http qui expose les données du fichier sqlite3 sous forme d'une
+//! API REST (JSON) : les jours (lecture, enregistrement, effacement), les mois, les années et les
+//! rapports sur une période. Les modifications sont enregistrées dans l'historique avec la source
+//! 'api'. Toutes les requêtes sont limitées à un profil (cf. fichier profile.rs). Le même serveur
+//! propose aussi des pages web pour la saisie depuis un navigateur (cf. fichier web.rs).
+//! - author : Thierry Probst <thierry.probst@free.fr>
+//! - version : 1.0.0
+//! - date : 19/10/2026
+use std::net::SocketAddr;
+use chrono::{Datelike, NaiveDate};
+use serde_json::{json, Value};
+use std::io::{Cursor, Read};
+use tiny_http::{Header, Method, Request, Response};
+use crate::ca::Ca;
+use crate::cadaosqlite::CaDaoSqlite;
+use crate::camonth::CaMonth;
+use crate::caperiod::CaPeriod;
+use crate::entry::Entry;
+use crate::error::{self, Error};
+use crate::history::SOURCE_API;
+use crate::i18n::msg;
+use crate::period::Period;
+use crate::profile::Profile;
+use crate::tag::{self, TagTotal};
+use crate::web;
+
+
+/// l'adresse d'écoute par défaut, limitée à la machine locale
+pub const DEFAULT_ADDR: &str = "127.0.0.1:8037";
+
+/// la taille maximale du contenu d'une requête (1 Mo), au delà la requête est refusée (413)
+pub const MAX_BODY: u64 = 1024 * 1024;
+
+/// la réponse à une requête de l'API : le code http et le contenu JSON
+type Reply = (u16, Value);
+
+/// le contenu d'une réponse
+#[derive(Debug)]
+pub enum Body {
+    /// un contenu JSON (API)
+    Json(Value),
+    /// une page HTML (pages web)
+    Html(String),
+    /// une redirection vers une autre url (après l'envoi d'un formulaire)
+    Redirect(String),
+}
+
+#[derive(Debug)]
+pub struct Server {
+    /// le chemin du fichier sqlite3
+    pub url: String,
+    /// le profil utilisé quand la requête ne précise pas ?profile=nom
+    pub profile: Profile,
+    /// le jeton attendu dans l'entête 'Authorization: Bearer <jeton>', None pour ne pas en exiger
+    pub token: Option<String>,
+}
+
+impl Server {
+    /// new : crée un Server
+    /// - params :
+    ///     - url -> le chemin du fichier sqlite3
+    ///     - profile -> le profil par défaut des requêtes
+    ///     - token -> le jeton d'authentification, None pour ne pas en exiger
+    /// - return : un objet Server
+    pub fn new( url: &str, profile: &Profile, token: Option<String> ) -> Server {
+        Server{ url: url.to_string(), profile: profile.clone(), token }
+    }
+
+    /// run : écoute les requêtes http et y répond, une par une, sans fin. Un jeton est
+    /// obligatoire si l'adresse d'écoute n'est pas locale.
+    /// - params :
+    ///     - &self -> une référence sur le Server
+    ///     - addr -> l'adresse d'écoute (ip:port)
+    /// - return : l'erreur Config (adresse invalide, jeton absent) ou Io si le serveur ne peut pas
+    ///   démarrer
+    pub fn run(&self, addr: &str) -> error::Result<()> {
+        let socket: SocketAddr = addr.parse().map_err( |_| Error::Config( msg!( "server.bad_address", addr ) ) )?;
+        if !socket.ip().is_loopback() && self.token.is_none() {
+            return Err( Error::Config( msg!( "server.token_required", addr ) ) );
+        }
+        let server = tiny_http::Server::http( socket )
+            .map_err( |e| Error::Io( std::io::Error::other( msg!( "server.failed", e ) ) ) )?;
+        println!( "{}", msg!( "server.listening", addr, self.profile.name ) );
+
+        for mut request in server.incoming_requests() {
+            // un jeton passé dans l'url (?token=...) est conservé par le navigateur dans un cookie
+            let cookie = query_value( &split_url( request.url() ).1, "token" ).filter( |t| self.token.as_ref() == Some(t) );
+            let (status, body) = self.handle( &mut request );
+            let mut response = Server::response( status, body );
+            if let Some(t) = cookie {
+                response.add_header( Header::from_bytes( "Set-Cookie", format!( "fde_token={t}; HttpOnly; SameSite=Strict; Path=/" ) )
+                    .unwrap() );
+            }
+            if let Err(e) = request.respond( response ) {
+                println!( "{}", msg!( "server.respond", e ) );
+            }
+        }
+        Ok(())
+    }
+
+    /// response : construit la réponse http
+    /// - params :
+    ///     - status -> le code http
+    ///     - body -> le contenu
+    /// - return : la réponse à envoyer
+    fn response(status: u16, body: Body) -> Response<Cursor<Vec<u8>>> {
+        let (content, content_type) = match &body {
+            Body::Json(v) => (v.to_string(), "application/json; charset=utf-8"),
+            Body::Html(h) => (h.clone(), "text/html; charset=utf-8"),
+            Body::Redirect(_) => (String::new(), "text/plain; charset=utf-8"),
+        };
+        let mut response = Response::from_string( content ).with_status_code( status )
+            .with_header( Header::from_bytes( "Content-Type", content_type ).unwrap() );
+        if let Body::Redirect(location) = body {
+            response.add_header( Header::from_bytes( "Location", location ).unwrap() );
+        }
+        response
+    }
+
+    /// handle : vérifie l'authentification puis dirige la requête vers l'API ou vers les pages web
+    /// - params :
+    ///     - &self -> une référence sur le Server
+    ///     - request -> la requête reçue
+    /// - return : la réponse (code http et contenu)
+    fn handle(&self, request: &mut Request) -> (u16, Body) {
+        let (path, query) = split_url( request.url() );
+        let is_web = path == "/" || path.starts_with( "/web" );
+        if !self.authorized( request, &query ) {
+            return if is_web { (401, Body::Html( web::unauthorized() )) }
+                else { (401, Body::Json( Server::error( 401, &msg!( "api.token" ) ).1 )) };
+        }
+        if !same_origin( request ) {
+            return if is_web { (403, Body::Html( web::forbidden() )) }
+                else { (403, Body::Json( Server::error( 403, &msg!( "api.origin" ) ).1 )) };
+        }
+        let profile = match query_value( &query, "profile" ) {
+            None => self.profile.clone(),
+            Some(name) => match CaDaoSqlite::new( &self.url, self.profile.id ).profile( Some(&name) ) {
+                Ok(p) => p,
+                Err(rusqlite::Error::QueryReturnedNoRows) =>
+                    return (404, Body::Json( Server::error( 404, &msg!( "api.profile", name ) ).1 )),
+                Err(e) => return (500, Body::Json( Server::error( 500, &e.to_string() ).1 )),
+            },
+        };
+        let tags = tag::parse_list( &query_values( &query, "tag" ).join(",") );
+        // le contenu est lu dans la limite de MAX_BODY, un octet de plus signale un contenu trop long
+        let mut body = String::new();
+        if request.as_reader().take( MAX_BODY + 1 ).read_to_string( &mut body ).is_err() {
+            return (400, Body::Json( Server::error( 400, &msg!( "api.unreadable" ) ).1 ));
+        }
+        if body.len() as u64 > MAX_BODY {
+            return if is_web { (413, Body::Html( web::too_large() )) }
+                else { (413, Body::Json( Server::error( 413, &msg!( "api.too_large", MAX_BODY / 1024 ) ).1 )) };
+        }
+
+        let parts: Vec<&str> = path.trim_matches('/').split('/').collect();
+        if is_web {
+            return web::page( &self.url, &profile, request.method(), &parts, &body );
+        }
+        let (status, v) = match (request.method(), parts.as_slice()) {
+            (Method::Get, ["days", d]) => self.get_day( &profile, d ),
+            (Method::Put, ["days", d]) => self.put_day( &profile, d, &body ),
+            (Method::Delete, ["days", d]) => self.delete_day( &profile, d ),
+            (Method::Get, ["months", m]) => self.get_month( &profile, m, &tags ),
+            (Method::Get, ["years", y]) => self.get_year( &profile, y, &tags ),
+            (Method::Get, ["reports"]) => self.get_report( &profile, query_value( &query, "period" ), &tags ),
+            (_, ["days", _]) | (_, ["months", _]) | (_, ["years", _]) | (_, ["reports"]) =>
+                Server::error( 405, &msg!( "api.method" ) ),
+            _ => Server::error( 404, &msg!( "api.resource", path ) ),
+        };
+        (status, Body::Json( v ))
+    }
+
+    /// authorized : vérifie le jeton de la requête
+    /// - params :
+    ///     - &self -> une référence sur le Server
+    ///     - request -> la requête reçue
+    ///     - query -> les paramètres de l'url
+    /// - return : true si aucun jeton n'est exigé ou si l'entête Authorization, le cookie fde_token
+    ///   ou le paramètre ?token= le contient
+    fn authorized(&self, request: &Request, query: &[(String, String)]) -> bool {
+        let Some(token) = &self.token else { return true; };
+        query_value( query, "token" ).is_some_and( |t| same_token( &t, token ) ) || request.headers().iter().any( |h|
+            ( h.field.equiv( "Authorization" )
+                && h.value.as_str().strip_prefix( "Bearer " ).is_some_and( |t| same_token( t.trim(), token ) ) )
+            || ( h.field.equiv( "Cookie" )
+                && h.value.as_str().split(';').any( |c| c.trim().strip_prefix( "fde_token=" ).is_some_and( |t| same_token( t, token ) ) ) ) )
+    }
+
+    /// dao : crée le CaDaoSqlite d'un profil, dont les modifications sont signées 'api'
+    /// - params :
+    ///     - &self -> une référence sur le Server
+    ///     - profile -> le profil concerné
+    /// - return : un objet CaDaoSqlite
+    fn dao(&self, profile: &Profile) -> CaDaoSqlite {
+        CaDaoSqlite{ source: SOURCE_API, ..CaDaoSqlite::new( &self.url, profile.id ) }
+    }
+
+    /// error : construit une réponse d'erreur
+    /// - params :
+    ///     - status -> le code http
+    ///     - message -> le message d'erreur
+    /// - return : la réponse
+    fn error(status: u16, message: &str) -> Reply {
+        (status, json!({ "error": message }))
+    }
+
+    /// sqlite_error : construit la réponse correspondant à une erreur sqlite3
+    /// - params : e -> l'erreur
+    /// - return : la réponse (404 si la donnée n'existe pas, 500 sinon)
+    fn sqlite_error(e: rusqlite::Error) -> Reply {
+        match e {
+            rusqlite::Error::QueryReturnedNoRows => Server::error( 404, &msg!( "api.not_found" ) ),
+            e => Server::error( 500, &e.to_string() ),
+        }
+    }
+
+    /// get_day : GET /days/yyyy-mm-dd, les données d'un jour
+    /// - params :
+    ///     - profile -> le profil concerné
+    ///     - d -> la date (yyyy-mm-dd)
+    /// - return : la réponse, 404 si le jour n'a pas de données
+    fn get_day(&self, profile: &Profile, d: &str) -> Reply {
+        let Ok(day) = NaiveDate::parse_from_str( d, "%Y-%m-%d" ) else { return Server::error( 400, &msg!( "api.date" ) ); };
+        let dao = self.dao( profile );
+        match dao.exist( &day ).and_then( |exist| if exist { dao.get( &day ).map( Some ) } else { Ok(None) } ) {
+            Ok(Some(c)) => (200, ca_json( &c )),
+            Ok(None) => Server::error( 404, &msg!( "api.no_data", d ) ),
+            Err(e) => Server::sqlite_error( e ),
+        }
+    }
+
+    /// put_day : PUT /days/yyyy-mm-dd, crée ou remplace les données d'un jour. Le contenu est un
+    /// objet JSON avec soit la liste "entries" des activités, soit directement les valeurs
+    /// (activity, ca, hours, hsup, comment) d'une activité unique, et optionnellement la liste
+    /// "tags" (les tags existants sont conservés si elle est absente).
+    /// - params :
+    ///     - profile -> le profil concerné
+    ///     - d -> la date (yyyy-mm-dd)
+    ///     - body -> le contenu JSON de la requête
+    /// - return : la réponse, contenant le jour enregistré
+    fn put_day(&self, profile: &Profile, d: &str, body: &str) -> Reply {
+        let Ok(day) = NaiveDate::parse_from_str( d, "%Y-%m-%d" ) else { return Server::error( 400, &msg!( "api.date" ) ); };
+        let Ok(v) = serde_json::from_str::<Value>( body ) else { return Server::error( 400, &msg!( "api.not_json" ) ); };
+        let dao = self.dao( profile );
+        let (existing, tags) = match dao.entries( &day ).and_then( |e| Ok(( e, dao.tags( &day )? )) ) {
+            Ok(r) => r,
+            Err(e) => return Server::sqlite_error( e ),
+        };
+
+        let entries = match v.get( "entries" ) {
+            Some(Value::Array(list)) => list.iter().map( |e| {
+                let id = e.get( "id" ).and_then( |i| i.as_i64() ).filter( |i| existing.iter().any( |x| x.id == Some(*i) ) );
+                entry_from_json( e, Entry{ id, ..Entry::default() } )
+            }).collect::<Result<Vec<Entry>, String>>(),
+            Some(_) => Err( msg!( "api.list", "entries" ) ),
+            None => entry_from_json( &v, existing.first().cloned().unwrap_or_default() ).map( |e| vec![ e ] ),
+        };
+        let tags = match v.get( "tags" ) {
+            None => Ok(tags),
+            Some(Value::Array(list)) => list.iter().map( |t| t.as_str().map( |s| s.to_string() )
+                .ok_or( msg!( "api.text_list", "tags" ) ) ).collect::<Result<Vec<String>, String>>()
+                .map( |l| tag::parse_list( &l.join(",") ) ),
+            Some(_) => Err( msg!( "api.list", "tags" ) ),
+        };
+        let (entries, tags) = match (entries, tags) {
+            (Ok(e), Ok(t)) => (e, t),
+            (Err(e), _) | (_, Err(e)) => return Server::error( 400, &e ),
+        };
+        if entries.is_empty() {
+            return Server::error( 400, &msg!( "api.empty_day" ) );
+        }
+
+        let mut c = Ca{ profile: profile.id, date: day.to_string(), ca: 0.0, hours: 0.0, hsup: 0.0, comment: None, tags, entries };
+        c.total();
+        match dao.update( &c ).and_then( |_| dao.get( &day ) ) {
+            Ok(c) => (200, ca_json( &c )),
+            Err(e) => Server::sqlite_error( e ),
+        }
+    }
+
+    /// delete_day : DELETE /days/yyyy-mm-dd, efface un jour (il est placé dans la corbeille)
+    /// - params :
+    ///     - profile -> le profil concerné
+    ///     - d -> la date (yyyy-mm-dd)
+    /// - return : la réponse, 404 si le jour n'a pas de données
+    fn delete_day(&self, profile: &Profile, d: &str) -> Reply {
+        let Ok(day) = NaiveDate::parse_from_str( d, "%Y-%m-%d" ) else { return Server::error( 400, &msg!( "api.date" ) ); };
+        let dao = self.dao( profile );
+        match dao.exist( &day ).and_then( |exist| if exist { dao.get( &day ).and_then( |c| dao.del( c ) ).map( |_| true ) } else { Ok(false) } ) {
+            Ok(true) => (200, json!({ "deleted": d })),
+            Ok(false) => Server::error( 404, &msg!( "api.no_data", d ) ),
+            Err(e) => Server::sqlite_error( e ),
+        }
+    }
+
+    /// get_month : GET /months/yyyy-mm, le rapport d'un mois (totaux, prime et détail des jours)
+    /// - params :
+    ///     - profile -> le profil concerné
+    ///     - m -> le mois (yyyy-mm)
+    ///     - tags -> les tags auxquels limiter le rapport (?tag=...)
+    /// - return : la réponse
+    fn get_month(&self, profile: &Profile, m: &str, tags: &[String]) -> Reply {
+        let Ok(day) = NaiveDate::parse_from_str( &format!( "{m}-01" ), "%Y-%m-%d" ) else {
+            return Server::error( 400, &msg!( "api.month" ) );
+        };
+        let mut cmonth = CaMonth::new( day, profile, tags );
+        let result = CaDaoSqlite::new( &self.url, profile.id ).open_session().and_then( |dao| {
+            cmonth.retrieve_with( &dao )?;
+            // les jours du rapport sont lus sans leurs activités ni leurs tags, qui sont ajoutés
+            // pour que chaque jour soit le même que celui de GET /days
+            for c in cmonth.datas.iter_mut() {
+                let Ok(d) = c.date.parse::<NaiveDate>() else { continue; };
+                c.entries = dao.entries( &d )?;
+                c.tags = dao.tags( &d )?;
+            }
+            Ok(())
+        } );
+        match result {
+            Ok(()) => (200, month_json( &cmonth )),
+            Err(e) => Server::sqlite_error( e ),
+        }
+    }
+
+    /// get_year : GET /years/yyyy, le rapport d'une année et le détail de chacun de ses mois
+    /// - params :
+    ///     - profile -> le profil concerné
+    ///     - y -> l'année (yyyy)
+    ///     - tags -> les tags auxquels limiter le rapport (?tag=...)
+    /// - return : la réponse
+    fn get_year(&self, profile: &Profile, y: &str, tags: &[String]) -> Reply {
+        let Some(period) = y.parse::<i32>().ok().and_then( Period::year ) else { return Server::error( 400, &msg!( "api.year" ) ); };
+        let mut months = Vec::new();
+        for day in period.months() {
+            let mut cmonth = CaMonth::new( day, profile, tags );
+            if let Err(e) = cmonth.retrieve_datas( self.url.clone() ) { return Server::sqlite_error( e ); }
+            months.push( json!({ "month": format!( "{}-{:02}", day.year(), day.month() ), "ca": cmonth.ca,
+                "hours": cmonth.hours, "hsup": cmonth.hsup, "worked_days": cmonth.worked_days(), "delta": cmonth.delta(),
+                "prime": cmonth.prime() }) );
+        }
+        let mut cperiod = CaPeriod::new( period, profile, tags );
+        match cperiod.retrieve_datas( self.url.clone() ) {
+            Ok(()) => {
+                let mut v = period_json( &cperiod );
+                v["months"] = Value::Array( months );
+                (200, v)
+            },
+            Err(e) => Server::sqlite_error( e ),
+        }
+    }
+
+    /// get_report : GET /reports?period=..., le rapport d'une période (mm/yyyy, Tn/yyyy, yyyy)
+    /// - params :
+    ///     - profile -> le profil concerné
+    ///     - period -> la période (?period=...)
+    ///     - tags -> les tags auxquels limiter le rapport (?tag=...)
+    /// - return : la réponse
+    fn get_report(&self, profile: &Profile, period: Option<String>, tags: &[String]) -> Reply {
+        let Some(period) = period.as_deref().and_then( Period::parse ) else {
+            return Server::error( 400, &msg!( "api.period" ) );
+        };
+        let mut cperiod = CaPeriod::new( period, profile, tags );
+        match cperiod.retrieve_datas( self.url.clone() ) {
+            Ok(()) => (200, period_json( &cperiod )),
+            Err(e) => Server::sqlite_error( e ),
+        }
+    }
+}
+
+/// same_token : compare un jeton reçu au jeton du serveur en un temps qui ne dépend pas du
+/// nombre de caractères identiques (toutes les paires d'octets sont comparées), pour que le jeton
+/// ne puisse pas être deviné caractère par caractère
+/// - params :
+///     - given -> le jeton reçu
+///     - token -> le jeton du serveur
+/// - return : true si les deux jetons sont identiques
+fn same_token(given: &str, token: &str) -> bool {
+    let (a, b) = (given.as_bytes(), token.as_bytes());
+    a.len() == b.len() && a.iter().zip( b ).fold( 0, |acc, (x, y)| acc | (x ^ y) ) == 0
+}
+
+/// same_origin : protège contre les requêtes forgées depuis un autre site (CSRF) : une requête qui
+/// modifie des données (autre que GET ou HEAD) n'est acceptée que si son en-tête Origin, ou à
+/// défaut Referer, désigne le serveur lui-même (en-tête Host). Les navigateurs envoient toujours
+/// l'un des deux, une requête qui n'en a aucun (curl, script) est acceptée.
+/// - params : request -> la requête reçue
+/// - return : true si la requête peut être traitée
+fn same_origin(request: &Request) -> bool {
+    if matches!( request.method(), Method::Get | Method::Head ) { return true; }
+    let header = |name: &str| request.headers().iter().find( |h| h.field.as_str().as_str().eq_ignore_ascii_case( name ) ).map( |h| h.value.as_str().to_string() );
+    let Some(source) = header( "Origin" ).or_else( || header( "Referer" ) ) else { return true; };
+    // l'hôte de l'origine (Origin: null, envoyé par les pages isolées, est refusé)
+    let host = source.split_once( "://" ).map( |(_, rest)| rest.split( '/' ).next().unwrap_or( "" ).to_string() );
+    host.is_some() && host == header( "Host" )
+}
+
+/// split_url : sépare le chemin et les paramètres d'une url
+/// - params : url -> l'url de la requête (/days/2024-05-24?profile=Nat)
+/// - return : le chemin et la liste des paramètres (nom, valeur) décodés
+fn split_url( url: &str ) -> (String, Vec<(String, String)>) {
+    let (path, query) = url.split_once('?').unwrap_or( (url, "") );
+    (percent_decode( path ), parse_query( query ))
+}
+
+/// parse_query : décode des paramètres de la forme nom=valeur&nom=valeur (paramètres d'une url ou
+/// contenu d'un formulaire)
+/// - params : query -> la chaine encodée
+/// - return : la liste des paramètres (nom, valeur) décodés
+pub fn parse_query( query: &str ) -> Vec<(String, String)> {
+    query.split('&').filter( |p| !p.is_empty() )
+        .map( |p| { let (k, v) = p.split_once('=').unwrap_or( (p, "") ); (percent_decode( k ), percent_decode( v )) } )
+        .collect()
+}
+
+/// query_value : la valeur d'un paramètre de l'url
+/// - params :
+///     - query -> les paramètres de l'url
+///     - name -> le nom du paramètre
+/// - return : la valeur du premier paramètre portant ce nom, None s'il est absent
+pub fn query_value( query: &[(String, String)], name: &str ) -> Option<String> {
+    query.iter().find( |(k, _)| k == name ).map( |(_, v)| v.clone() )
+}
+
+/// query_values : les valeurs d'un paramètre répété de l'url (?tag=a&tag=b)
+/// - params :
+///     - query -> les paramètres de l'url
+///     - name -> le nom du paramètre
+/// - return : un Vecteur contenant les valeurs
+fn query_values( query: &[(String, String)], name: &str ) -> Vec<String> {
+    query.iter().filter( |(k, _)| k == name ).map( |(_, v)| v.clone() ).collect()
+}
+
+/// percent_decode : décode une partie d'url (%C3%A9 => é, + => espace)
+/// - params : s -> la chaine encodée
+/// - return : la chaine décodée (les séquences invalides sont conservées telles quelles)
+fn percent_decode( s: &str ) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity( bytes.len() );
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes.get( i + 1..i + 3 ).and_then( |h| std::str::from_utf8( h ).ok() )
+            .and_then( |h| u8::from_str_radix( h, 16 ).ok() );
+        match (bytes[i], hex) {
+            (b'%', Some(b)) => { out.push( b ); i += 2; },
+            (b'+', _) => out.push( b' ' ),
+            (b, _) => out.push( b ),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy( &out ).to_string()
+}
+
+/// entry_from_json : complète une activité avec les valeurs d'un objet JSON
+/// - params :
+///     - v -> l'objet JSON (activity, ca, hours, hsup, comment, tous optionnels)
+///     - e -> l'activité à compléter
+/// - return : l'activité, ou un message si une valeur est invalide
+fn entry_from_json( v: &Value, mut e: Entry ) -> Result<Entry, String> {
+    if !v.is_object() { return Err( msg!( "api.entry" ) ); }
+    let number = |name: &str, current: f32| -> Result<f32, String> {
+        match v.get( name ) {
+            None => Ok(current),
+            Some(n) => n.as_f64().filter( |n| *n >= 0.0 ).map( |n| n as f32 )
+                .ok_or( msg!( "api.positive", name ) ),
+        }
+    };
+    e.ca = number( "ca", e.ca )?;
+    e.hours = number( "hours", e.hours )?;
+    e.hsup = number( "hsup", e.hsup )?;
+    match v.get( "activity" ) {
+        None => (),
+        Some(Value::String(s)) => e.activity = s.clone(),
+        Some(_) => return Err( msg!( "api.text", "activity" ) ),
+    }
+    match v.get( "comment" ) {
+        None => (),
+        Some(Value::Null) => e.comment = None,
+        Some(Value::String(s)) => e.comment = if s.is_empty() { None } else { Some( s.clone() ) },
+        Some(_) => return Err( msg!( "api.text", "comment" ) ),
+    }
+    e.validate()?;
+    Ok(e)
+}
+
+/// entry_json : la représentation JSON d'une activité
+/// - params : e -> l'activité
+/// - return : un objet JSON
+fn entry_json( e: &Entry ) -> Value {
+    json!({ "id": e.id, "activity": e.activity, "ca": e.ca, "hours": e.hours, "hsup": e.hsup, "comment": e.comment })
+}
+
+/// ca_json : la représentation JSON d'un jour et de ses activités
+/// - params : c -> le jour
+/// - return : un objet JSON
+fn ca_json( c: &Ca ) -> Value {
+    json!({ "date": c.date, "ca": c.ca, "hours": c.hours, "hsup": c.hsup, "comment": c.comment, "tags": c.tags,
+        "entries": c.entries.iter().map( entry_json ).collect::<Vec<Value>>() })
+}
+
+/// tag_totals_json : la représentation JSON des totaux par tag
+/// - params : v -> les totaux
+/// - return : une liste JSON
+fn tag_totals_json( v: &[TagTotal] ) -> Value {
+    Value::Array( v.iter().map( |t| json!({ "tag": t.tag, "ca": t.ca, "hours": t.hours, "days": t.days }) ).collect() )
+}
+
+/// month_json : la représentation JSON du rapport d'un mois
+/// - params : m -> le mois, dont les données ont été récupérées
+/// - return : un objet JSON
+fn month_json( m: &CaMonth ) -> Value {
+    json!({ "profile": m.profile.name, "month": format!( "{}-{:02}", m.day.year(), m.day.month() ), "tags": m.tags,
+        "ca": m.ca, "hours": m.hours, "hsup": m.hsup, "worked_days": m.worked_days(), "threshold": m.profile.threshold,
+        "delta": m.delta(), "prime": m.prime(), "tag_totals": tag_totals_json( &m.tag_totals ),
+        "days": m.datas.iter().map( ca_json ).collect::<Vec<Value>>() })
+}
+
+/// period_json : la représentation JSON du rapport d'une période
+/// - params : p -> la période, dont les données ont été récupérées
+/// - return : un objet JSON
+fn period_json( p: &CaPeriod ) -> Value {
+    json!({ "profile": p.profile.name, "period": p.period.to_string(), "tags": p.tags, "ca": p.ca, "hours": p.hours,
+        "hsup": p.hsup, "worked_days": p.days, "ca_per_hour": p.ca_per_hour(), "prime": p.prime,
+        "tag_totals": tag_totals_json( &p.tag_totals ) })
+}
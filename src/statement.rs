@@ -0,0 +1,143 @@
+//! # statement :
+//! Ce module génère le relevé mensuel envoyé à l'employeur à partir d'un CaMonth : un en-tête
+//! (nom et période), le tableau des jours, les totaux et le calcul de la prime (seuil, delta,
+//! taux, prime). Le relevé est un document HTML, ou un PDF si fde est compilé avec la
+//! fonctionnalité pdf (cargo build --features pdf).
+//! - author : Thierry Probst <thierry.probst@free.fr>
+//! - version : 1.0.0
+//! - date : 19/10/2026
+use crate::camonth::CaMonth;
+use crate::i18n::{self, msg};
+use crate::markup::escape;
+
+
+/// columns : les titres des colonnes du tableau des jours
+/// - params : aucun
+/// - return : un Vecteur contenant les 5 titres, dans la langue de l'interface
+pub fn columns() -> Vec<String> {
+    msg!( "statement.columns" ).split(',').map( |c| c.to_string() ).collect()
+}
+
+/// title : le titre du relevé
+/// - params : m -> le mois concerné
+/// - return : le titre (nom et période)
+pub fn title( m: &CaMonth ) -> String {
+    msg!( "statement.title", m.profile.name, i18n::month( &m.day ) )
+}
+
+/// rows : les lignes du tableau des jours
+/// - params : m -> le mois concerné
+/// - return : un Vecteur contenant une ligne par jour, une colonne par titre (cf. columns)
+pub fn rows( m: &CaMonth ) -> Vec<[String; 5]> {
+    m.datas.iter().map( |c| [
+        i18n::iso_date( &c.date ),
+        i18n::number( c.ca as f64, 2 ),
+        i18n::decimal( c.hours ),
+        i18n::decimal( c.hsup ),
+        c.comment.clone().unwrap_or_default(),
+    ]).collect()
+}
+
+/// breakdown : les totaux du mois et le calcul de la prime
+/// - params : m -> le mois concerné
+/// - return : un Vecteur contenant les lignes (libellé, valeur)
+pub fn breakdown( m: &CaMonth ) -> Vec<(String, String)> {
+    vec![
+        (msg!( "statement.worked_days" ), m.worked_days().to_string()),
+        (msg!( "statement.ca" ), format!( "{} €", i18n::number( m.ca, 2 ) )),
+        (msg!( "statement.hours" ), i18n::number( m.hours, 2 )),
+        (msg!( "statement.hsup" ), i18n::number( m.hsup, 2 )),
+        (msg!( "statement.threshold" ), format!( "{} €", i18n::number( m.profile.threshold, 2 ) )),
+        (msg!( "statement.delta" ), format!( "{} €", i18n::number( m.delta(), 2 ) )),
+        (msg!( "statement.rate" ), format!( "{} %", i18n::number( m.profile.rate * 100.0, 2 ) )),
+        (msg!( "statement.prime" ), format!( "{} €", i18n::number( m.prime(), 2 ) )),
+    ]
+}
+
+/// html : le relevé au format HTML (autonome, le style est inclus dans la page)
+/// - params : m -> le mois concerné, dont les données ont été récupérées
+/// - return : le document HTML
+pub fn html( m: &CaMonth ) -> String {
+    let mut h = format!( "<!DOCTYPE html><html lang=\"{1}\"><head><meta charset=\"utf-8\"><title>{0}</title><style>
+    body {{ font-family: sans-serif; margin: 2em; }}
+    table {{ border-collapse: collapse; margin-bottom: 2em; }}
+    td, th {{ border: 1px solid #999; padding: 0.2em 0.6em; }}
+    td.num {{ text-align: right; }}
+    </style></head><body><h1>{0}</h1>", escape( &title( m ) ), i18n::lang().code() );
+
+    h.push_str( "<table><tr>" );
+    for c in columns().iter() {
+        h.push_str( &format!( "<th>{}</th>", escape( c ) ) );
+    }
+    h.push_str( "</tr>" );
+    for r in rows( m ).iter() {
+        h.push_str( &format!( "<tr><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td>\
+            <td>{}</td></tr>", r[0], r[1], r[2], r[3], escape( &r[4] ) ) );
+    }
+    h.push_str( &format!( "<tr><th>{}</th><th class=\"num\">{}</th><th class=\"num\">{}</th><th class=\"num\">{}</th>\
+        <th></th></tr></table>", msg!( "statement.total" ), i18n::number( m.ca, 2 ), i18n::decimal( m.hours ),
+        i18n::decimal( m.hsup ) ) );
+
+    h.push_str( &format!( "<h2>{}</h2><table>", msg!( "statement.prime_title" ) ) );
+    for (label, value) in breakdown( m ).iter() {
+        h.push_str( &format!( "<tr><td>{label}</td><td class=\"num\">{value}</td></tr>" ) );
+    }
+    h.push_str( "</table></body></html>" );
+    h
+}
+
+/// pdf : le relevé au format PDF (A4, police Helvetica intégrée aux lecteurs PDF)
+/// - params : m -> le mois concerné, dont les données ont été récupérées
+/// - return : le contenu du fichier PDF, ou le message d'erreur de printpdf
+#[cfg(feature = "pdf")]
+pub fn pdf( m: &CaMonth ) -> Result<Vec<u8>, String> {
+    use printpdf::{BuiltinFont, Mm, PdfDocument};
+
+    // les positions des colonnes (mm depuis le bord gauche) et la longueur maximale du commentaire
+    const X: [f32; 5] = [ 15.0, 45.0, 75.0, 100.0, 125.0 ];
+    const COMMENT_LEN: usize = 40;
+    let (doc, page, layer) = PdfDocument::new( title( m ), Mm(210.0), Mm(297.0), "relevé" );
+    let font = doc.add_builtin_font( BuiltinFont::Helvetica ).map_err( |e| e.to_string() )?;
+    let bold = doc.add_builtin_font( BuiltinFont::HelveticaBold ).map_err( |e| e.to_string() )?;
+    let mut layer = doc.get_page( page ).get_layer( layer );
+    let mut y = 280.0;
+
+    layer.use_text( title( m ), 16.0, Mm(X[0]), Mm(y), &bold );
+    y -= 12.0;
+    for (i, c) in columns().into_iter().enumerate() {
+        layer.use_text( c, 10.0, Mm(X[i]), Mm(y), &bold );
+    }
+    for r in rows( m ).iter() {
+        y -= 6.0;
+        if y < 20.0 {
+            let (p, l) = doc.add_page( Mm(210.0), Mm(297.0), "relevé" );
+            layer = doc.get_page( p ).get_layer( l );
+            y = 280.0;
+        }
+        for (i, v) in r.iter().enumerate() {
+            let v: String = if i == 4 && v.chars().count() > COMMENT_LEN {
+                v.chars().take( COMMENT_LEN ).chain( "…".chars() ).collect()
+            } else { v.clone() };
+            layer.use_text( v, 10.0, Mm(X[i]), Mm(y), &font );
+        }
+    }
+    y -= 8.0;
+    layer.use_text( msg!( "statement.total" ), 10.0, Mm(X[0]), Mm(y), &bold );
+    layer.use_text( i18n::number( m.ca, 2 ), 10.0, Mm(X[1]), Mm(y), &bold );
+    layer.use_text( i18n::decimal( m.hours ), 10.0, Mm(X[2]), Mm(y), &bold );
+    layer.use_text( i18n::decimal( m.hsup ), 10.0, Mm(X[3]), Mm(y), &bold );
+
+    if y < 80.0 {
+        let (p, l) = doc.add_page( Mm(210.0), Mm(297.0), "relevé" );
+        layer = doc.get_page( p ).get_layer( l );
+        y = 290.0;
+    }
+    y -= 14.0;
+    layer.use_text( msg!( "statement.prime_title" ), 12.0, Mm(X[0]), Mm(y), &bold );
+    for (label, value) in breakdown( m ).iter() {
+        y -= 6.0;
+        layer.use_text( label.clone(), 10.0, Mm(X[0]), Mm(y), &font );
+        layer.use_text( value.clone(), 10.0, Mm(X[3]), Mm(y), &font );
+    }
+    doc.save_to_bytes().map_err( |e| e.to_string() )
+}
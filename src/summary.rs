@@ -0,0 +1,60 @@
+//! # summary :
+//! Ce module définit une structure Summary représentant les totaux et les moyennes mensuelles
+//! d'une période de plusieurs mois (en général 12 mois glissants ou une année), ainsi que le
+//! nombre de mois au dessus du seuil de la prime. Ce sont les chiffres utilisés pour renégocier
+//! le seuil. Les valeurs sont calculées par une seule requête (cf. CaDaoSqlite::summary).
+//! - author : Thierry Probst <thierry.probst@free.fr>
+//! - version : 1.0.0
+//! - date : 19/10/2026
+use chrono::Months;
+use crate::period::Period;
+
+
+#[derive(Debug)]
+pub struct Summary {
+    /// la période concernée
+    pub period: Period,
+    /// le chiffre d'affaire de la période
+    pub ca: f64,
+    /// le nombre d'heures
+    pub hours: f64,
+    /// le nombre d'heures supplémentaires
+    pub hsup: f64,
+    /// le nombre de mois ayant des données
+    pub months: usize,
+    /// le nombre de mois dont le chiffre d'affaire dépasse le seuil de la prime
+    pub above: usize,
+}
+
+impl Summary {
+    /// rolling : la période des 12 mois glissants se terminant par un mois
+    /// - params : month -> le dernier mois de la période
+    /// - return : la période
+    pub fn rolling( month: &Period ) -> Period {
+        Period{ start: month.start - Months::new( 11 ), nb_months: 12 }
+    }
+
+    /// average : la moyenne mensuelle d'une valeur, sur les seuls mois ayant des données (les mois
+    /// à venir de l'année en cours ne font donc pas baisser la moyenne)
+    /// - params :
+    ///     - self -> la strucure concernée
+    ///     - total -> le total de la période
+    /// - return : un float (0 si aucun mois n'a de données)
+    pub fn average(&self, total: f64) -> f64 {
+        if self.months == 0 { 0.0 } else { total / self.months as f64 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn average_over_months_entered() {
+        let year = Period::parse( "2024" ).unwrap();
+        let s = Summary{ period: year.clone(), ca: 3000.0, hours: 300.0, hsup: 0.0, months: 3, above: 0 };
+        assert_eq!( s.average( s.ca ), 1000.0 );
+        let s = Summary{ period: year, ca: 0.0, hours: 0.0, hsup: 0.0, months: 0, above: 0 };
+        assert_eq!( s.average( s.ca ), 0.0 );
+    }
+}
@@ -0,0 +1,128 @@
+//! # svg :
+//! Ce module dessine les graphiques du bilan annuel dans un fichier SVG autonome (sans feuille de
+//! style ni script externe) : le chiffre d'affaire de chaque mois comparé au seuil de la prime,
+//! le chiffre d'affaire par heure et le cumul des heures supplémentaires. Les totaux mensuels
+//! sont calculés par CaDaoSqlite::totals, ce sont les mêmes sommes que celles d'un CaMonth.
+//! - author : Thierry Probst <thierry.probst@free.fr>
+//! - version : 1.0.0
+//! - date : 19/10/2026
+use crate::i18n::{self, msg};
+use crate::markup::escape;
+use crate::period::PeriodTotal;
+use crate::profile::Profile;
+
+
+/// la largeur du document
+const WIDTH: f64 = 800.0;
+/// la hauteur d'un graphique
+const PANEL_HEIGHT: f64 = 260.0;
+/// la hauteur réservée au titre du document
+const HEADER: f64 = 50.0;
+/// les marges autour de la zone de tracé d'un graphique : gauche, droite, haut, bas
+const MARGINS: (f64, f64, f64, f64) = ( 80.0, 30.0, 40.0, 40.0 );
+/// le nombre d'intervalles de l'axe des valeurs
+const TICKS: usize = 4;
+/// la couleur des barres et des courbes, et celle des mois au dessus du seuil
+const COLORS: (&str, &str) = ( "#4a7ebb", "#3a9a5b" );
+/// la couleur du seuil de la prime
+const THRESHOLD_COLOR: &str = "#c0392b";
+
+/// charts : le document SVG contenant les trois graphiques
+/// - params :
+///     - title -> le titre du document (la période)
+///     - profile -> le profil concerné (nom et seuil de la prime)
+///     - totals -> les totaux de chaque mois de la période (cf. Step::fill)
+/// - return : le document SVG
+pub fn charts( title: &str, profile: &Profile, totals: &[PeriodTotal] ) -> String {
+    let height = HEADER + 3.0 * PANEL_HEIGHT;
+    let labels: Vec<String> = totals.iter().map( |t| i18n::short_month( &t.start ) ).collect();
+    let ca: Vec<Option<f64>> = totals.iter().map( |t| Some( t.ca ) ).collect();
+    let ca_per_hour: Vec<Option<f64>> = totals.iter()
+        .map( |t| if t.hours > 0.0 { Some( t.ca / t.hours ) } else { None } ).collect();
+    let hsup: Vec<Option<f64>> = totals.iter().scan( 0.0, |sum, t| { *sum += t.hsup; Some( Some( *sum ) ) } ).collect();
+
+    let mut s = format!( "<svg xml:lang=\"{2}\" xmlns=\"http://www.w3.org/2000/svg\" width=\"{WIDTH}\" height=\"{height}\" \
+        viewBox=\"0 0 {WIDTH} {height}\" font-family=\"sans-serif\" font-size=\"11\">\n\
+        <title>{0}</title>\n<rect width=\"100%\" height=\"100%\" fill=\"white\"/>\n\
+        <text x=\"{1}\" y=\"30\" font-size=\"18\" font-weight=\"bold\">{0}</text>\n",
+        escape( &format!( "{} - {title}", profile.name ) ), MARGINS.0, i18n::lang().code() );
+    s.push_str( &panel( HEADER, &msg!( "svg.ca" ), "€", &labels, &ca, Some( profile.threshold ) ) );
+    s.push_str( &panel( HEADER + PANEL_HEIGHT, &msg!( "svg.ca_hour" ), "€/h", &labels, &ca_per_hour, None ) );
+    s.push_str( &panel( HEADER + 2.0 * PANEL_HEIGHT, &msg!( "svg.hsup" ), "h", &labels, &hsup, None ) );
+    s.push_str( "</svg>\n" );
+    s
+}
+
+/// panel : un graphique, en barres si un seuil est indiqué, sinon en courbe
+/// - params :
+///     - top -> la position verticale du graphique dans le document
+///     - title, unit -> le titre du graphique et l'unité des valeurs
+///     - labels -> les libellés de l'axe horizontal
+///     - values -> une valeur par libellé (None pour un point absent de la courbe)
+///     - threshold -> le seuil, tracé en pointillés au dessus des barres
+/// - return : les éléments SVG du graphique
+fn panel( top: f64, title: &str, unit: &str, labels: &[String], values: &[Option<f64>], threshold: Option<f64> ) -> String {
+    let (x0, y0) = (MARGINS.0, top + MARGINS.2);
+    let (w, h) = (WIDTH - MARGINS.0 - MARGINS.1, PANEL_HEIGHT - MARGINS.2 - MARGINS.3);
+    let step = scale_step( values.iter().flatten().cloned().chain( threshold ).fold( 0.0, f64::max ) );
+    let max = step * TICKS as f64;
+    let slot = w / labels.len().max( 1 ) as f64;
+    let x = |i: usize| x0 + slot * (i as f64 + 0.5);
+    let y = |v: f64| y0 + h - v / max * h;
+
+    let mut s = format!( "<g>\n<text x=\"{x0}\" y=\"{}\" font-size=\"14\" font-weight=\"bold\">{}</text>\n",
+        top + 24.0, escape( title ) );
+    for k in 0..=TICKS {
+        let v = step * k as f64;
+        s.push_str( &format!( "<line x1=\"{x0}\" y1=\"{0:.1}\" x2=\"{1}\" y2=\"{0:.1}\" stroke=\"#ddd\"/>\
+            <text x=\"{2}\" y=\"{3:.1}\" text-anchor=\"end\">{4} {unit}</text>\n",
+            y( v ), x0 + w, x0 - 6.0, y( v ) + 4.0, i18n::number( v, if step < 1.0 { 1 } else { 0 } ) ) );
+    }
+    for (i, l) in labels.iter().enumerate() {
+        s.push_str( &format!( "<text x=\"{:.1}\" y=\"{}\" text-anchor=\"middle\">{l}</text>\n", x( i ), y0 + h + 16.0 ) );
+    }
+
+    match threshold {
+        Some(t) => {
+            for (i, v) in values.iter().enumerate() {
+                let Some(v) = v.filter( |v| *v > 0.0 ) else { continue; };
+                let color = if v > t { COLORS.1 } else { COLORS.0 };
+                s.push_str( &format!( "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" fill=\"{color}\">\
+                    <title>{} : {} {unit}</title></rect>\n", x( i ) - slot * 0.35, y( v ), slot * 0.7, y0 + h - y( v ), labels[i],
+                    i18n::number( v, 2 ) ) );
+            }
+            s.push_str( &format!( "<line x1=\"{x0}\" y1=\"{0:.1}\" x2=\"{1}\" y2=\"{0:.1}\" stroke=\"{THRESHOLD_COLOR}\" \
+                stroke-dasharray=\"6 4\"/><text x=\"{1}\" y=\"{2:.1}\" text-anchor=\"end\" fill=\"{THRESHOLD_COLOR}\">\
+                {3} : {4} {unit}</text>\n", y( t ), x0 + w, y( t ) - 4.0, escape( &msg!( "label.threshold" ) ), i18n::number( t, 2 ) ) );
+        },
+        None => {
+            // une courbe par suite de points présents
+            for segment in (0..values.len()).collect::<Vec<usize>>().split( |i| values[*i].is_none() ) {
+                let points: Vec<String> = segment.iter()
+                    .filter_map( |i| values[*i].map( |v| format!( "{:.1},{:.1}", x( *i ), y( v ) ) ) ).collect();
+                if points.len() > 1 {
+                    s.push_str( &format!( "<polyline points=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"2\"/>\n",
+                        points.join(" "), COLORS.0 ) );
+                }
+            }
+            for (i, v) in values.iter().enumerate() {
+                let Some(v) = v else { continue; };
+                s.push_str( &format!( "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"3\" fill=\"{}\"><title>{} : {} {unit}</title></circle>\n",
+                    x( i ), y( *v ), COLORS.0, labels[i], i18n::number( *v, 2 ) ) );
+            }
+        },
+    }
+    s.push_str( &format!( "<line x1=\"{x0}\" y1=\"{0}\" x2=\"{1}\" y2=\"{0}\" stroke=\"#333\"/>\n</g>\n", y0 + h, x0 + w ) );
+    s
+}
+
+/// scale_step : l'intervalle entre deux graduations de l'axe des valeurs (1, 2 ou 5 fois une
+/// puissance de 10), pour que TICKS intervalles couvrent la valeur maximale
+/// - params : max -> la valeur maximale
+/// - return : l'intervalle
+fn scale_step( max: f64 ) -> f64 {
+    if max <= 0.0 { return 1.0; }
+    let raw = max / TICKS as f64;
+    let magnitude = 10f64.powf( raw.log10().floor() );
+    [ 1.0, 2.0, 5.0, 10.0 ].iter().map( |m| m * magnitude ).find( |s| *s >= raw ).unwrap_or( 10.0 * magnitude )
+}
@@ -0,0 +1,40 @@
+//! # tag :
+//! Ce module regroupe la gestion des tags (marché, salon, formation, maladie, ...) associés à
+//! une journée de travail. Les tags sont enregistrés dans la table CA_TAG qui associe une date
+//! à un nom de tag.
+//! - author : Thierry Probst <thierry.probst@free.fr>
+//! - version : 1.0.0
+//! - date : 19/10/2026
+
+
+#[derive(Debug)]
+pub struct TagTotal {
+    /// le nom du tag
+    pub tag: String,
+    /// le chiffre d'affaire des jours portant ce tag
+    pub ca: f64,
+    /// le nombre d'heures des jours portant ce tag
+    pub hours: f64,
+    /// le nombre de jours portant ce tag
+    pub days: usize,
+}
+
+/// parse_list : transforme une saisie de la forme "marché, Salon" en une liste de tags
+/// normalisés (en minuscules, sans espaces superflus ni doublons)
+/// - params : s -> la chaine saisie
+/// - return : un Vecteur contenant les tags
+pub fn parse_list( s: &str ) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for t in s.split(',').map( |t| t.trim().to_lowercase() ) {
+        if !t.is_empty() && !tags.contains( &t ) { tags.push( t ); }
+    }
+    tags
+}
+
+/// filters : récupère les filtres --tag=xxx présents parmi les arguments de la ligne de commande
+/// - params : args -> les arguments
+/// - return : un Vecteur contenant les tags demandés (vide si aucun filtre)
+pub fn filters( args: &[String] ) -> Vec<String> {
+    let list: Vec<&str> = args.iter().filter_map( |a| a.strip_prefix("--tag=") ).collect();
+    parse_list( &list.join(",") )
+}
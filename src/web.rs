@@ -0,0 +1,296 @@
+//! # web :
+//! Ce module définit les pages web servies par le Server (cf. fichier server.rs) : le calendrier
+//! d'un mois avec son récapitulatif (delta et prime), et le formulaire de saisie d'un jour qui
+//! applique les mêmes contrôles que la saisie au terminal. Les pages sont entièrement générées
+//! par fde, sans feuille de style ni script externe.
+//! - author : Thierry Probst <thierry.probst@free.fr>
+//! - version : 1.0.0
+//! - date : 19/10/2026
+use chrono::{Datelike, Local, Months, NaiveDate};
+use tiny_http::Method;
+use crate::ca::Ca;
+use crate::cadaosqlite::CaDaoSqlite;
+use crate::camonth::CaMonth;
+use crate::entry::Entry;
+use crate::history::SOURCE_WEB;
+use crate::i18n::{self, msg};
+use crate::markup::escape;
+use crate::profile::Profile;
+use crate::server::{self, Body};
+use crate::tag;
+
+
+/// la feuille de style des pages, incluse dans chacune d'elles
+const STYLE: &str = "
+    body { font-family: sans-serif; margin: 1em auto; max-width: 60em; padding: 0 1em; }
+    table { border-collapse: collapse; }
+    td, th { border: 1px solid #ccc; padding: 0.3em 0.5em; vertical-align: top; }
+    table.calendar { width: 100%; table-layout: fixed; }
+    table.calendar td { height: 4em; }
+    table.calendar td.worked { background: #eef6ee; }
+    td.num { text-align: right; }
+    .positive { color: #080; } .negative { color: #a00; }
+    .error { background: #fdd; border: 1px solid #a00; padding: 0.5em; }
+    input[type=text] { width: 8em; } input.comment { width: 20em; }
+    nav a { margin-right: 1em; }
+";
+
+/// une ligne du formulaire d'un jour : les valeurs d'une activité telles qu'elles sont saisies
+#[derive(Debug, Default)]
+struct Row {
+    /// l'identifiant de l'activité, None pour une nouvelle activité
+    id: Option<i64>,
+    /// l'activité concernée
+    activity: String,
+    /// le chiffre d'affaire saisi
+    ca: String,
+    /// le nombre d'heures saisi
+    hours: String,
+    /// le nombre d'heures supplémentaires saisi
+    hsup: String,
+    /// le commentaire saisi
+    comment: String,
+}
+
+/// page : dirige une requête vers la bonne page
+/// - params :
+///     - url -> le chemin du fichier sqlite3
+///     - profile -> le profil concerné
+///     - method -> la méthode http
+///     - parts -> les éléments du chemin de l'url (["web", "month", "2024-05"] par exemple)
+///     - body -> le contenu de la requête (formulaire)
+/// - return : la réponse (code http et contenu)
+pub fn page( url: &str, profile: &Profile, method: &Method, parts: &[&str], body: &str ) -> (u16, Body) {
+    match (method, parts) {
+        (Method::Get, [""]) | (Method::Get, ["web"]) =>
+            (303, Body::Redirect( link( &format!( "/web/month/{}", Local::now().format("%Y-%m") ), profile ) )),
+        (Method::Get, ["web", "month", m]) => month_page( url, profile, m ),
+        (Method::Get, ["web", "day", d]) => day_page( url, profile, d ),
+        (Method::Post, ["web", "day", d]) => save_day( url, profile, d, body ),
+        _ => (404, Body::Html( layout( &msg!( "web.not_found" ), &format!( "<p>{}</p>", msg!( "web.not_found_text" ) ) ) )),
+    }
+}
+
+/// unauthorized : la page affichée quand le jeton est absent ou invalide
+/// - params : aucun
+/// - return : le contenu HTML
+pub fn unauthorized() -> String {
+    layout( &msg!( "web.unauthorized" ), &format!( "<p>{}</p>", msg!( "web.unauthorized_text" ) ) )
+}
+
+/// forbidden : la page affichée quand un formulaire est envoyé depuis un autre site
+/// - params : aucun
+/// - return : le contenu HTML
+pub fn forbidden() -> String {
+    layout( &msg!( "web.forbidden" ), &format!( "<p>{}</p>", msg!( "web.forbidden_text" ) ) )
+}
+
+/// too_large : la page affichée quand un formulaire dépasse la taille maximale d'une requête
+/// - params : aucun
+/// - return : le contenu HTML
+pub fn too_large() -> String {
+    layout( &msg!( "web.too_large" ), &format!( "<p>{}</p>", msg!( "api.too_large", server::MAX_BODY / 1024 ) ) )
+}
+
+/// month_page : le calendrier d'un mois et son récapitulatif
+/// - params :
+///     - url -> le chemin du fichier sqlite3
+///     - profile -> le profil concerné
+///     - m -> le mois (yyyy-mm)
+/// - return : la réponse (code http et contenu)
+fn month_page( url: &str, profile: &Profile, m: &str ) -> (u16, Body) {
+    let Ok(first) = NaiveDate::parse_from_str( &format!( "{m}-01" ), "%Y-%m-%d" ) else {
+        return (400, Body::Html( layout( &msg!( "web.bad_month" ), &format!( "<p class=\"error\">{}</p>", msg!( "web.bad_month_text" ) ) ) ));
+    };
+    let mut cmonth = CaMonth::new( first, profile, &[] );
+    if let Err(e) = cmonth.retrieve_datas( url.to_string() ) {
+        return (500, Body::Html( layout( &msg!( "error.title" ), &format!( "<p class=\"error\">{}</p>", escape( &e.to_string() ) ) ) ));
+    }
+
+    let title = format!( "{} ({})", i18n::month( &first ), profile.name );
+    let mut html = format!( "<nav><a href=\"{}\">&lt; {}</a><a href=\"{}\">{} &gt;</a></nav>",
+        link( &format!( "/web/month/{}", (first - Months::new( 1 )).format("%Y-%m") ), profile ), msg!( "web.previous" ),
+        link( &format!( "/web/month/{}", (first + Months::new( 1 )).format("%Y-%m") ), profile ), msg!( "web.next" ) );
+
+    if cmonth.datas.is_empty() {
+        html.push_str( &format!( "<p>{}</p>", msg!( "month.no_data" ) ) );
+    }
+    html.push_str( &format!( "<table class=\"calendar\"><tr>{}</tr><tr>",
+        msg!( "weekdays" ).split(',').map( |d| format!( "<th>{d}</th>" ) ).collect::<String>() ) );
+    let offset = first.weekday().num_days_from_monday() as usize;
+    html.push_str( &"<td></td>".repeat( offset ) );
+    let nb_days = ((first + Months::new( 1 )) - first).num_days() as usize;
+    for i in 0..nb_days {
+        let day = first + chrono::Days::new( i as u64 );
+        if i > 0 && (offset + i).is_multiple_of( 7 ) { html.push_str( "</tr><tr>" ); }
+        let data = cmonth.datas.iter().find( |c| c.date == day.to_string() );
+        let class = if data.map( |c| c.ca > 0.0 || c.hours > 0.0 ).unwrap_or( false ) { " class=\"worked\"" } else { "" };
+        html.push_str( &format!( "<td{class}><a href=\"{}\">{}</a>", link( &format!( "/web/day/{day}" ), profile ), day.day() ) );
+        if let Some(c) = data {
+            html.push_str( &format!( "<br>{} €<br>{} h", i18n::decimal( c.ca ), i18n::decimal( c.hours ) ) );
+        }
+        html.push_str( "</td>" );
+    }
+    html.push_str( &"<td></td>".repeat( (7 - (offset + nb_days) % 7) % 7 ) );
+    html.push_str( "</tr></table>" );
+
+    let sign = if cmonth.delta() > 0.0 { "positive" } else { "negative" };
+    html.push_str( &format!( "<h2>{}</h2><table>", msg!( "web.summary" ) ) );
+    let lines = [
+        (msg!( "label.ca" ), i18n::number( cmonth.ca, 2 ), ""),
+        (msg!( "label.hours" ), i18n::number( cmonth.hours, 2 ), ""),
+        (msg!( "web.hsup" ), i18n::number( cmonth.hsup, 2 ), ""),
+        (msg!( "web.worked_days" ), cmonth.worked_days().to_string(), ""),
+        (msg!( "label.threshold" ), i18n::number( profile.threshold, 2 ), ""),
+        (msg!( "label.delta" ), i18n::number( cmonth.delta(), 2 ), sign),
+        (msg!( "label.prime" ), i18n::number( cmonth.prime(), 2 ), ""),
+    ];
+    for (label, value, class) in lines.iter() {
+        html.push_str( &format!( "<tr><td>{label}</td><td class=\"num {class}\">{value}</td></tr>" ) );
+    }
+    html.push_str( "</table>" );
+    (200, Body::Html( layout( &title, &html ) ))
+}
+
+/// day_page : le formulaire de saisie d'un jour. Rien n'est enregistré tant que le formulaire
+/// n'est pas envoyé.
+/// - params :
+///     - url -> le chemin du fichier sqlite3
+///     - profile -> le profil concerné
+///     - d -> la date (yyyy-mm-dd)
+/// - return : la réponse (code http et contenu)
+fn day_page( url: &str, profile: &Profile, d: &str ) -> (u16, Body) {
+    let Ok(day) = NaiveDate::parse_from_str( d, "%Y-%m-%d" ) else {
+        return (400, Body::Html( layout( &msg!( "web.bad_date" ), &format!( "<p class=\"error\">{}</p>", msg!( "web.bad_date_text" ) ) ) ));
+    };
+    let dao = CaDaoSqlite::new( url, profile.id );
+    match dao.exist( &day ).and_then( |exist| if exist { dao.get( &day ).map( Some ) } else { Ok(None) } ) {
+        Ok(c) => {
+            let (rows, tags) = match c {
+                Some(c) => (c.entries.iter().map( |e| Row{ id: e.id, activity: e.activity.clone(), ca: e.ca.to_string(),
+                    hours: e.hours.to_string(), hsup: e.hsup.to_string(), comment: e.comment.clone().unwrap_or_default() } )
+                    .collect(), c.tags.join(", ")),
+                None => (Vec::new(), String::new()),
+            };
+            (200, Body::Html( day_form( profile, &day, &rows, &tags, None ) ))
+        },
+        Err(e) => (500, Body::Html( layout( &msg!( "error.title" ), &format!( "<p class=\"error\">{}</p>", escape( &e.to_string() ) ) ) )),
+    }
+}
+
+/// save_day : enregistre le formulaire d'un jour. Chaque activité est validée comme au terminal
+/// (cf. Entry::with_values), le formulaire est affiché à nouveau avec le message d'erreur si
+/// une saisie n'est pas valide. Un jour dont toutes les activités sont retirées est effacé (il
+/// est placé dans la corbeille).
+/// - params :
+///     - url -> le chemin du fichier sqlite3
+///     - profile -> le profil concerné
+///     - d -> la date (yyyy-mm-dd)
+///     - body -> le contenu du formulaire
+/// - return : la réponse (redirection vers le mois en cas de succès)
+fn save_day( url: &str, profile: &Profile, d: &str, body: &str ) -> (u16, Body) {
+    let Ok(day) = NaiveDate::parse_from_str( d, "%Y-%m-%d" ) else {
+        return (400, Body::Html( layout( &msg!( "web.bad_date" ), &format!( "<p class=\"error\">{}</p>", msg!( "web.bad_date_text" ) ) ) ));
+    };
+    let form = server::parse_query( body );
+    let field = |name: &str| server::query_value( &form, name ).unwrap_or_default();
+    let dao = CaDaoSqlite{ source: SOURCE_WEB, ..CaDaoSqlite::new( url, profile.id ) };
+    let existing = match dao.entries( &day ) {
+        Ok(v) => v,
+        Err(e) => return (500, Body::Html( layout( &msg!( "error.title" ), &format!( "<p class=\"error\">{}</p>", escape( &e.to_string() ) ) ) )),
+    };
+
+    let mut rows = Vec::new();
+    for i in (0..).take_while( |i| server::query_value( &form, &format!( "ca_{i}" ) ).is_some() ) {
+        if !field( &format!( "remove_{i}" ) ).is_empty() { continue; }
+        let row = Row{ id: field( &format!( "id_{i}" ) ).parse().ok().filter( |id| existing.iter().any( |e| e.id == Some(*id) ) ),
+            activity: field( &format!( "activity_{i}" ) ), ca: field( &format!( "ca_{i}" ) ), hours: field( &format!( "hours_{i}" ) ),
+            hsup: field( &format!( "hsup_{i}" ) ), comment: field( &format!( "comment_{i}" ) ) };
+        let blank = [ &row.activity, &row.ca, &row.hours, &row.hsup, &row.comment ].iter().all( |v| v.trim().is_empty() );
+        if row.id.is_none() && blank { continue; }
+        rows.push( row );
+    }
+    let tags = field( "tags" );
+
+    let mut entries = Vec::new();
+    for r in rows.iter() {
+        let e = Entry{ id: r.id, activity: r.activity.trim().to_string(), ..Entry::default() };
+        match e.with_values( &r.ca, &r.hours, &r.hsup, &r.comment ) {
+            Ok(e) => entries.push( e ),
+            Err(msg) => return (400, Body::Html( day_form( profile, &day, &rows, &tags, Some( &msg ) ) )),
+        }
+    }
+
+    let mut c = Ca{ profile: profile.id, date: day.to_string(), ca: 0.0, hours: 0.0, hsup: 0.0, comment: None,
+        tags: tag::parse_list( &tags ), entries };
+    c.total();
+    let result = if !c.entries.is_empty() { dao.update( &c ) }
+        else if !existing.is_empty() { dao.del( c ) }
+        else { Ok(()) };
+    match result {
+        Ok(()) => (303, Body::Redirect( link( &format!( "/web/month/{}", day.format("%Y-%m") ), profile ) )),
+        Err(e) => (500, Body::Html( layout( &msg!( "error.title" ), &format!( "<p class=\"error\">{}</p>", escape( &e.to_string() ) ) ) )),
+    }
+}
+
+/// day_form : le formulaire d'un jour, une ligne par activité plus une ligne vide pour en ajouter
+/// une
+/// - params :
+///     - profile -> le profil concerné
+///     - day -> le jour concerné
+///     - rows -> les activités
+///     - tags -> les tags, séparés par des virgules
+///     - error -> le message d'erreur de la saisie précédente
+/// - return : le contenu HTML
+fn day_form( profile: &Profile, day: &NaiveDate, rows: &[Row], tags: &str, error: Option<&str> ) -> String {
+    let mut html = format!( "<nav><a href=\"{}\">&lt; {}</a></nav>",
+        link( &format!( "/web/month/{}", day.format("%Y-%m") ), profile ), msg!( "web.back" ) );
+    if let Some(msg) = error {
+        html.push_str( &format!( "<p class=\"error\">{}</p>", escape( msg ) ) );
+    }
+    html.push_str( &format!( "<form method=\"post\" action=\"{}\"><table>\
+        <tr>{}</tr>", link( &format!( "/web/day/{day}" ), profile ),
+        msg!( "web.columns" ).split(',').map( |c| format!( "<th>{c}</th>" ) ).collect::<String>() ) );
+    let empty = Row::default();
+    for (i, r) in rows.iter().chain( std::iter::once( &empty ) ).enumerate() {
+        html.push_str( &format!( "<tr><td><input type=\"hidden\" name=\"id_{i}\" value=\"{}\">\
+            <input type=\"text\" name=\"activity_{i}\" value=\"{}\"></td>\
+            <td><input type=\"text\" name=\"ca_{i}\" value=\"{}\" inputmode=\"decimal\"></td>\
+            <td><input type=\"text\" name=\"hours_{i}\" value=\"{}\" inputmode=\"decimal\"></td>\
+            <td><input type=\"text\" name=\"hsup_{i}\" value=\"{}\" inputmode=\"decimal\"></td>\
+            <td><input type=\"text\" class=\"comment\" name=\"comment_{i}\" value=\"{}\"></td>\
+            <td>{}</td></tr>",
+            r.id.map( |id| id.to_string() ).unwrap_or_default(), escape( &r.activity ), escape( &r.ca ), escape( &r.hours ),
+            escape( &r.hsup ), escape( &r.comment ),
+            if r.id.is_some() { format!( "<input type=\"checkbox\" name=\"remove_{i}\" value=\"1\">" ) } else { String::new() } ) );
+    }
+    html.push_str( &format!( "</table><p>{} : \
+        <input type=\"text\" class=\"comment\" name=\"tags\" value=\"{}\"></p>\
+        <p><button type=\"submit\">{}</button></p></form>", msg!( "web.tags" ), escape( tags ), msg!( "web.save" ) ) );
+    layout( &format!( "{} ({})", i18n::date( day ), profile.name ), &html )
+}
+
+/// layout : la structure commune des pages
+/// - params :
+///     - title -> le titre de la page
+///     - content -> le contenu HTML de la page
+/// - return : la page HTML complète
+fn layout( title: &str, content: &str ) -> String {
+    format!( "<!DOCTYPE html><html lang=\"{1}\"><head><meta charset=\"utf-8\">\
+        <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\
+        <title>fde - {0}</title><style>{STYLE}</style></head><body><h1>{0}</h1>{content}</body></html>", escape( title ),
+        i18n::lang().code() )
+}
+
+/// link : l'url d'une page pour le profil concerné
+/// - params :
+///     - path -> le chemin de la page
+///     - profile -> le profil concerné
+/// - return : l'url avec le paramètre ?profile=nom
+fn link( path: &str, profile: &Profile ) -> String {
+    let name: String = profile.name.bytes().map( |b| match b {
+        b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' => (b as char).to_string(),
+        b => format!( "%{b:02X}" ),
+    }).collect();
+    format!( "{path}?profile={name}" )
+}
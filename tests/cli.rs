@@ -0,0 +1,157 @@
+//! # cli :
+//! les tests du programme fde : chaque test lance le binaire dans un répertoire temporaire (qui
+//! contient son propre fichier data/fildeclair.sq3) et lui passe les saisies de l'utilisateur par
+//! l'entrée standard.
+//! - author : Thierry Probst <thierry.probst@free.fr>
+//! - version : 1.0.0
+//! - date : 19/10/2026
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+
+/// un répertoire de travail temporaire, supprimé à la fin du test
+struct Workdir {
+    path: PathBuf,
+}
+
+/// la sortie d'une exécution de fde
+struct Run {
+    code: i32,
+    stdout: String,
+    stderr: String,
+}
+
+impl Workdir {
+    fn new(name: &str) -> Workdir {
+        let path = std::env::temp_dir().join( format!( "fde-cli-{name}-{}", std::process::id() ) );
+        let _ = std::fs::remove_dir_all( &path );
+        std::fs::create_dir_all( path.join( "data" ) ).unwrap();
+        Workdir{ path }
+    }
+
+    /// fde : lance fde (en anglais) avec des arguments et des saisies
+    fn fde(&self, args: &[&str], input: impl AsRef<[u8]>) -> Run {
+        let mut child = Command::new( env!( "CARGO_BIN_EXE_fde" ) )
+            .arg( "--lang=en" ).args( args ).current_dir( &self.path )
+            .stdin( Stdio::piped() ).stdout( Stdio::piped() ).stderr( Stdio::piped() )
+            .spawn().unwrap();
+        child.stdin.take().unwrap().write_all( input.as_ref() ).unwrap();
+        let output = child.wait_with_output().unwrap();
+        Run{ code: output.status.code().unwrap_or( -1 ), stdout: String::from_utf8_lossy( &output.stdout ).to_string(),
+            stderr: String::from_utf8_lossy( &output.stderr ).to_string() }
+    }
+}
+
+impl Drop for Workdir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all( &self.path );
+    }
+}
+
+#[test]
+fn help() {
+    let w = Workdir::new( "help" );
+    let r = w.fde( &["--help"], "" );
+    assert_eq!( r.code, 0 );
+    assert!( r.stdout.contains( "Exit codes" ) );
+}
+
+#[test]
+fn exit_codes() {
+    let w = Workdir::new( "codes" );
+    let r = w.fde( &["compare"], "" );
+    assert_eq!( r.code, 2 );
+    assert!( r.stderr.contains( "usage : fde compare" ) );
+    assert_eq!( w.fde( &["-d=31/02/2024"], "" ).code, 2 );
+    assert_eq!( w.fde( &["bogus"], "" ).code, 2 );
+    assert_eq!( w.fde( &["--profile=nobody", "-m"], "" ).code, 4 );
+    assert_eq!( w.fde( &["pay", "--month=05/2024"], "" ).code, 4 );
+    assert_eq!( w.fde( &["pay", "--month"], "" ).code, 2 );
+    assert_eq!( w.fde( &["backup", "restore", "missing.sq3"], "" ).code, 6 );
+    // une saisie illisible (qui n'est pas de l'UTF-8) est une erreur d'entrée / sortie
+    assert_eq!( w.fde( &["-d=02/05/2024"], b"m\n\xff\n".as_slice() ).code, 6 );
+}
+
+#[test]
+fn modify_a_day_then_show_its_month() {
+    let w = Workdir::new( "modify" );
+    // une saisie négative est refusée et recommencée
+    let r = w.fde( &["-d=02/05/2024"], "m\n-5\n7\n0\n\n150\n7\n0\nclient Dupont\nsalon\nq\n" );
+    assert_eq!( r.code, 0, "{}", r.stderr );
+    assert!( r.stdout.contains( "has been created" ) );
+    assert!( r.stdout.contains( "the day is now : (2024-05-02 : 150 [7/0" ) );
+
+    let r = w.fde( &["-m=05/2024"], "q\n" );
+    assert_eq!( r.code, 0, "{}", r.stderr );
+    assert!( r.stdout.contains( "Revenue  =   150.00" ) );
+    assert!( r.stdout.contains( "2024-05-02 :  150 / 7" ) );
+    assert!( r.stdout.contains( "salon" ) );
+
+    let r = w.fde( &["search", "dupont"], "" );
+    assert!( r.stdout.contains( "1 day(s)" ) );
+}
+
+#[test]
+fn navigate_between_days_and_months() {
+    let w = Workdir::new( "navigate" );
+    // jour suivant, son mois, puis le 10 du mois
+    let r = w.fde( &["-d=31/05/2024"], "n\nM\n10\nq\n" );
+    assert_eq!( r.code, 0, "{}", r.stderr );
+    assert!( r.stdout.contains( "the selected day is : (2024-06-01" ) );
+    assert!( r.stdout.contains( "month 2024-06" ) );
+    assert!( r.stdout.contains( "the selected day is : (2024-06-10" ) );
+    // les jours seulement consultés ne sont pas enregistrés
+    assert!( !r.stdout.contains( "has been created" ) );
+    assert!( w.fde( &["-m=06/2024"], "q\n" ).stdout.contains( "no data for this month" ) );
+}
+
+#[test]
+fn delete_then_restore_from_trash() {
+    let w = Workdir::new( "trash" );
+    // le 1er reste dans le mois affiché après l'effacement du 2
+    let r = w.fde( &["-d=01/05/2024"], "m\n100\n7\n0\n\n\nn\nm\n150\n7\n0\n\n\nd\ny\nq\n" );
+    assert_eq!( r.code, 0, "{}", r.stderr );
+    assert!( r.stdout.contains( "2024-05-01 :  100 / 7" ) );
+    assert!( w.fde( &["trash"], "" ).stdout.contains( "2024-05-02" ) );
+    // la corbeille n'est vidée qu'à la demande, et seulement des jours plus anciens que sa durée
+    let r = w.fde( &["trash", "purge"], "" );
+    assert_eq!( r.code, 0, "{}", r.stderr );
+    assert!( r.stdout.contains( "0 activity(ies) permanently removed" ) );
+
+    let r = w.fde( &["trash", "restore", "02/05/2024"], "" );
+    assert_eq!( r.code, 0, "{}", r.stderr );
+    let r = w.fde( &["-d=02/05/2024"], "" );
+    assert!( r.stdout.contains( "(2024-05-02 : 150 [7/0" ) );
+}
+
+#[test]
+fn delete_the_only_day_of_a_month() {
+    let w = Workdir::new( "last" );
+    // après l'effacement, le mois affiché est vide
+    let r = w.fde( &["-d=02/05/2024"], "m\n150\n7\n0\n\n\nd\ny\nq\n" );
+    assert_eq!( r.code, 0, "{}", r.stderr );
+    assert!( r.stdout.contains( "no data for this month" ) );
+    assert!( r.stdout.contains( "Revenue  =     0.00" ) );
+}
+
+#[test]
+fn end_of_input_quits() {
+    let w = Workdir::new( "eof" );
+    let r = w.fde( &["-d=today"], "" );
+    assert_eq!( r.code, 0, "{}", r.stderr );
+}
+
+#[test]
+fn empty_month() {
+    let w = Workdir::new( "empty" );
+    let r = w.fde( &["-m=02/2024"], "q\n" );
+    assert_eq!( r.code, 0, "{}", r.stderr );
+    assert!( r.stdout.contains( "Revenue  =     0.00" ) );
+    assert!( r.stdout.contains( "no data for this month" ) );
+    assert!( r.stdout.contains( "Mon Tue Wed Thu Fri Sat Sun" ) );
+    assert!( r.stdout.contains( " 26  27  28  29\n" ) );
+
+    assert_eq!( w.fde( &["compare", "01/2024", "02/2024"], "" ).code, 0 );
+    assert_eq!( w.fde( &["summary", "02/2024"], "" ).code, 0 );
+}